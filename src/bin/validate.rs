@@ -1,88 +1,194 @@
 use anyhow::{Context, Result};
-use serde_json::Value;
 use std::path::Path;
 
-use panopticon::{
-    content::{loader, validator},
-    core::country::CountryDef,
+use panopticon::content::{
+    config::ValidateConfig,
+    db::ContentDb,
+    diagnostics::{self, codes, Diagnostic, Summary},
+    focus_analysis::FocusAnalysis,
+    loader, schemas, validator,
 };
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+    Sarif,
+}
+
+const USAGE: &str = "usage: validate [ROOT] [--conflicts] [--strict] [--deny-warnings] [--require-schemas] [--emit-schemas] [--focus-report TAG] [--format text|json|sarif]";
+
 fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let conflicts_mode = args.iter().any(|a| a == "--conflicts");
+    // Fail on content files that no pattern in schemas/registry.yaml covers
+    let require_schemas = args.iter().any(|a| a == "--require-schemas");
+    // Report fields serde would silently drop, minus extensions allowed in validate.yaml
+    let strict = args.iter().any(|a| a == "--strict");
+    // Fail on warnings as well as errors, for CI
+    let deny_warnings = args.iter().any(|a| a == "--deny-warnings");
+    let mut format = Format::Text;
+    let mut focus_report = None;
+    let mut root = Path::new(".");
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--format" => {
+                format = match iter.next().map(String::as_str) {
+                    Some("text") => Format::Text,
+                    Some("json") => Format::Json,
+                    Some("sarif") => Format::Sarif,
+                    other => anyhow::bail!("unknown --format {:?}; expected text, json or sarif", other.unwrap_or("")),
+                }
+            }
+            "--focus-report" => {
+                focus_report = Some(iter.next().context("--focus-report needs a country tag")?.as_str());
+            }
+            "--conflicts" | "--require-schemas" | "--strict" | "--deny-warnings" | "--emit-schemas" => (),
+            a if !a.starts_with("--") => root = Path::new(a),
+            // A mistyped flag would otherwise turn its check off without a word
+            other => anyhow::bail!("unknown option {}\n{}", other, USAGE),
+        }
+    }
+
+    if args.iter().any(|a| a == "--emit-schemas") {
+        // Regenerate schemas/ from the Rust content types
+        for path in schemas::emit_schemas(&root.join("schemas"))? {
+            println!("Wrote {}", path.display());
+        }
+        return Ok(());
+    }
+
+    if let Some(tag) = focus_report {
+        return print_focus_report(root, tag);
+    }
+
+    if format != Format::Text {
+        return machine_readable(root, format, require_schemas, strict, deny_warnings);
+    }
+
     println!("Running panopticon validate (content loader + schema checks)");
 
-    let args: Vec<String> = std::env::args().collect();
-    let root = if args.len() > 1 { Path::new(&args[1]) } else { Path::new(".") };
-    
+    // Resolve mod manifests first so dependency problems are reported on their own
+    let mods = loader::discover_mods(root)
+        .with_context(|| format!("resolving mods in {}", root.join("mods").display()))?;
+    if mods.is_empty() {
+        println!("No mods installed");
+    } else {
+        println!("Mod load order:");
+        for (i, m) in mods.iter().enumerate() {
+            println!("  {}. {} ({} v{})", i + 1, m.id, m.name, m.version);
+        }
+    }
+
     // Load all content files
-    let files = loader::load_game_and_mods(root)
+    let content = loader::load_content(root)
         .with_context(|| format!("loading game and mods from {}", root.display()))?;
+    let files = &content.files;
     println!("Loaded {} content files (game + mods)", files.len());
 
-    let mut errors = Vec::new();
+    if conflicts_mode {
+        if content.conflicts.is_empty() {
+            println!("No conflicts: every file and entry has a single source.");
+        } else {
+            println!(
+                "{} overridden item(s), {} between mods:",
+                content.conflicts.conflicts.len(),
+                content.conflicts.between_mods().count()
+            );
+            print!("{}", content.conflicts);
+        }
+        return Ok(());
+    }
+
+    let config = ValidateConfig::load(root)?;
+    let mut errors: Vec<Diagnostic> = Vec::new();
 
     // Schema validation
-    let schemas_dir = root.join("schemas");
-    if schemas_dir.exists() {
-        for (path, contents) in &files {
-            if let Some(stem) = path.file_stem() {
-                let json_schema_path = schemas_dir.join(format!("{}.schema.json", stem.to_string_lossy()));
-                if json_schema_path.exists() {
-                    match validator::load_schema(&json_schema_path)
-                        .and_then(|schema| {
-                            // try parsing content as JSON first, then YAML -> JSON
-                            let doc: Value = match serde_json::from_str(contents) {
-                                Ok(v) => v,
-                                Err(_) => serde_yaml::from_str::<Value>(contents)?,
-                            };
-                            validator::validate_value(schema, &doc)
-                                .with_context(|| format!("validating {} against schema", path.display()))
-                        }) {
-                        Ok(_) => println!("✓ Schema validation passed for {}", path.display()),
-                        Err(e) => {
-                            eprintln!("✗ Schema validation failed for {}\n  {}", path.display(), e);
-                            errors.push(e);
-                        }
-                    }
-                }
-            }
+    for (path, diags) in validator::schema_diagnostics(&root.join("schemas"), files, require_schemas) {
+        if diags.is_empty() {
+            println!("✓ Schema validation passed for {}", path.display());
+        } else if diags.iter().all(|d| d.code == codes::SCHEMA_MISSING) {
+            eprintln!("✗ No schema covers {}", path.display());
+            errors.extend(diags);
+        } else {
+            eprintln!("✗ Schema validation failed for {}", path.display());
+            errors.extend(diags);
         }
     }
 
-    // Load and validate country definitions
-    // Only validate actual country definition files, not every file inside countries/*
-    let country_files = files.iter()
-        .filter(|(p, _)| p.ends_with("country.yaml"));
-    
-    for (path, contents) in country_files {
-        match serde_yaml::from_str::<CountryDef>(contents) {
-            Ok(_) => println!("✓ Valid country definition in {}", path.display()),
-            Err(e) => errors.push(e.into()),
-        }
+    // Parse everything once into typed content; parse failures surface as structural errors
+    let db = ContentDb::from_files(files);
+    for (tag, path) in &db.country_files {
+        println!("✓ Valid country definition {} in {}", tag, path.display());
     }
 
     // run structural validations (cross-file checks) AFTER schemas
-    match validator::structural_validations(&files) {
-        Ok(()) => (),
-        Err(e) => errors.push(e),
+    errors.extend(validator::structural_diagnostics(&db));
+    if strict {
+        errors.extend(validator::strict_diagnostics(&db, &config.strict));
     }
 
-    // Extra country sanity (duplicate with schema but keeps future extensibility)
-    for (path, contents) in files.iter().filter(|(p, _)| p.ends_with("country.yaml")) {
-        match serde_yaml::from_str::<CountryDef>(contents) {
-            Ok(_) => (),
-            Err(e) => errors.push(e.into()),
-        }
-        println!("Checked country file {}", path.display());
+    // Apply rule levels from validate.yaml and inline suppression comments
+    // Point at the files on disk, not the merged content, where suppression comments survive
+    let errors: Vec<Diagnostic> = errors.into_iter().map(|d| content.source_map.relocate(d)).collect();
+    let (errors, suppressed) = config.apply(errors, &content.sources);
+    let summary = Summary::of(&errors, suppressed);
+    for e in &errors {
+        eprintln!("{}", e.render(&content.sources));
     }
-
-    if errors.is_empty() {
+    if summary.fails(deny_warnings) {
+        eprintln!("Validation complete with {}.", summary);
+        Err(anyhow::anyhow!("validation failed"))
+    } else if errors.is_empty() && suppressed == 0 {
         println!("Validation complete. No errors.");
         Ok(())
     } else {
-        eprintln!("Validation complete with {} error(s).", errors.len());
-        for (i, e) in errors.into_iter().enumerate() {
-            eprintln!("[{}] {}", i + 1, e);
+        println!("Validation complete: {}.", summary);
+        Ok(())
+    }
+}
+
+/// Print reachability and minimum days for every focus in one country's tree.
+fn print_focus_report(root: &Path, tag: &str) -> Result<()> {
+    let db = ContentDb::load(root)?;
+    let tree = db.focus_tree(tag).with_context(|| format!("no focus tree for {}", tag))?;
+    println!("Focus tree for {} ({}): {} focus(es)", tag, db.focus_tree_files[tag].display(), tree.focuses.len());
+    print!("{}", FocusAnalysis::of(tree));
+    Ok(())
+}
+
+/// Run every check and print only the diagnostics document to stdout, so the
+/// output can be piped straight into an editor or CI annotation step.
+fn machine_readable(root: &Path, format: Format, require_schemas: bool, strict: bool, deny_warnings: bool) -> Result<()> {
+    let loaded = ValidateConfig::load(root).and_then(|config| Ok((config, loader::load_content(root)?)));
+    let (diags, suppressed) = match loaded {
+        Ok((config, content)) => {
+            let mut diags: Vec<Diagnostic> = validator::schema_diagnostics(&root.join("schemas"), &content.files, require_schemas)
+                .into_iter()
+                .flat_map(|(_, d)| d)
+                .collect();
+            let db = ContentDb::from_files(&content.files);
+            diags.extend(validator::structural_diagnostics(&db));
+            if strict {
+                diags.extend(validator::strict_diagnostics(&db, &config.strict));
+            }
+            let diags = diags.into_iter().map(|d| content.source_map.relocate(d)).collect();
+            config.apply(diags, &content.sources)
         }
+        Err(e) => (vec![Diagnostic::error(codes::LOAD_FAILED, format!("{:#}", e))], 0),
+    };
+    let summary = Summary::of(&diags, suppressed);
+
+    let doc = match format {
+        Format::Sarif => diagnostics::to_sarif(&diags),
+        _ => diagnostics::to_json(&diags, &summary),
+    };
+    println!("{}", serde_json::to_string_pretty(&doc)?);
+
+    if summary.fails(deny_warnings) {
         Err(anyhow::anyhow!("validation failed"))
+    } else {
+        Ok(())
     }
 }
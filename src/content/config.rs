@@ -0,0 +1,135 @@
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::content::db::{ContentKind, UnknownField};
+use crate::content::diagnostics::{codes, Diagnostic, Severity};
+use crate::content::suppress::Suppressions;
+
+/// Validator settings, read from `validate.yaml` in the content root.
+pub const CONFIG_FILE: &str = "validate.yaml";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ValidateConfig {
+    #[serde(default)]
+    pub strict: StrictConfig,
+    /// Per-code level overrides, e.g. `PAN024: error` or `PAN025: off`
+    #[serde(default)]
+    pub rules: HashMap<String, RuleLevel>,
+}
+
+/// Level a rule is reported at; `off` drops it entirely.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleLevel {
+    Error,
+    Warning,
+    Info,
+    Off,
+}
+
+impl RuleLevel {
+    pub fn severity(self) -> Option<Severity> {
+        match self {
+            RuleLevel::Error => Some(Severity::Error),
+            RuleLevel::Warning => Some(Severity::Warning),
+            RuleLevel::Info => Some(Severity::Info),
+            RuleLevel::Off => None,
+        }
+    }
+}
+
+/// Settings for `validate --strict`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StrictConfig {
+    /// Extension fields content may carry, per content type (`battalions`,
+    /// `focus_tree`, ...). An entry matches the key name anywhere in files of
+    /// that type; a trailing `*` matches by prefix, e.g. `x_*`.
+    #[serde(default)]
+    pub allow: HashMap<String, Vec<String>>,
+}
+
+impl ValidateConfig {
+    /// Read `validate.yaml` under `root`; a missing file means defaults.
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = root.join(CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = std::fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+        let config: ValidateConfig = serde_yaml::from_str(&text).with_context(|| format!("parsing {}", path.display()))?;
+        for kind in config.strict.allow.keys() {
+            if ContentKind::from_name(kind).is_none() {
+                let known: Vec<&str> = ContentKind::ALL.iter().map(|k| k.name()).collect();
+                bail!("{}: unknown content type '{}' in strict.allow (expected one of {})", path.display(), kind, known.join(", "));
+            }
+        }
+        for code in config.rules.keys() {
+            if codes::describe(code).is_none() {
+                bail!("{}: unknown diagnostic code '{}' in rules", path.display(), code);
+            }
+        }
+        Ok(config)
+    }
+
+    /// Apply the `rules` overrides, then the inline suppression comments in
+    /// `sources`, the files as they are on disk, which only silence what is
+    /// left a warning or info; `diags` must already point
+    /// there (see [`SourceMap::relocate`](crate::content::sources::SourceMap::relocate)).
+    /// Returns what is left to report and how many were dropped.
+    pub fn apply(&self, diags: Vec<Diagnostic>, sources: &[(PathBuf, String)]) -> (Vec<Diagnostic>, usize) {
+        let suppressions = Suppressions::scan(sources);
+        let total = diags.len();
+        let kept: Vec<Diagnostic> = diags
+            .into_iter()
+            .filter_map(|mut d| {
+                if let Some(level) = self.rules.get(d.code) {
+                    d.severity = level.severity()?;
+                }
+                Some(d)
+            })
+            .filter(|d| !suppressions.covers(d))
+            .collect();
+        let suppressed = total - kept.len();
+        (kept, suppressed)
+    }
+}
+
+impl StrictConfig {
+    pub fn allows(&self, field: &UnknownField) -> bool {
+        let name = field.name();
+        self.allow.get(field.kind.name()).is_some_and(|allowed| {
+            allowed.iter().any(|a| match a.strip_suffix('*') {
+                Some(prefix) => name.starts_with(prefix),
+                None => a == name,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::diagnostics::Location;
+
+    #[test]
+    fn rules_change_levels_and_comments_suppress() -> Result<()> {
+        let config: ValidateConfig = serde_yaml::from_str("rules:\n  PAN024: error\n  PAN025: off\n")?;
+        let path = PathBuf::from("game/map/provinces.yaml");
+        let files = vec![(path.clone(), "provinces:\n  - { id: 0 }  # validate: allow PAN023\n".to_string())];
+        let diags = vec![
+            Diagnostic::warning(codes::PROVINCE_WITHOUT_EDGES, "lonely").in_file(&path),
+            Diagnostic::warning(codes::SHARED_POSITION, "overlap").in_file(&path),
+            Diagnostic::warning(codes::MAP_ISLAND, "island").at(Location::at(&path, 2, 5)),
+            Diagnostic::warning(codes::MAP_ISLAND, "other island").in_file(&path),
+        ];
+        let (kept, suppressed) = config.apply(diags, &files);
+        let found: Vec<(&str, Severity)> = kept.iter().map(|d| (d.message.as_str(), d.severity)).collect();
+        assert_eq!(found, vec![("lonely", Severity::Error), ("other island", Severity::Warning)]);
+        assert_eq!(suppressed, 2);
+        Ok(())
+    }
+}
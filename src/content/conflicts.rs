@@ -0,0 +1,113 @@
+use serde::Serialize;
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::content::loader::ContentSource;
+
+/// What the winning source did to the losing definition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictAction {
+    Replaced,
+    Removed,
+}
+
+/// A file or list entry defined by more than one source, where only the last
+/// one to touch it survives.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Conflict {
+    pub path: PathBuf,
+    /// Entry key (an id, or `a-b` for edges); `None` for whole-file overrides.
+    pub key: Option<String>,
+    pub action: ConflictAction,
+    pub winner: ContentSource,
+    /// Earlier definitions that were discarded, oldest first.
+    pub losers: Vec<ContentSource>,
+}
+
+impl Conflict {
+    /// True when at least one discarded definition came from a mod rather than
+    /// the base game, i.e. two mods actually disagree.
+    pub fn between_mods(&self) -> bool {
+        self.losers.iter().any(|l| matches!(l, ContentSource::Mod(_)))
+    }
+}
+
+/// Every override recorded while loading content, in load order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ConflictReport {
+    pub conflicts: Vec<Conflict>,
+}
+
+impl ConflictReport {
+    pub fn is_empty(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+
+    /// Record that `winner` overrode the current definition of `path`/`key`,
+    /// which was last provided by `previous`. Repeated overrides of the same
+    /// item are folded into one conflict.
+    pub fn record(
+        &mut self,
+        path: PathBuf,
+        key: Option<String>,
+        action: ConflictAction,
+        previous: ContentSource,
+        winner: ContentSource,
+    ) {
+        if let Some(c) = self.conflicts.iter_mut().find(|c| c.path == path && c.key == key) {
+            c.losers.push(previous);
+            c.winner = winner;
+            c.action = action;
+        } else {
+            self.conflicts.push(Conflict { path, key, action, winner, losers: vec![previous] });
+        }
+    }
+
+    /// Only conflicts where two or more mods touched the same item.
+    pub fn between_mods(&self) -> impl Iterator<Item = &Conflict> {
+        self.conflicts.iter().filter(|c| c.between_mods())
+    }
+}
+
+impl fmt::Display for ConflictReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in &self.conflicts {
+            let item = match &c.key {
+                Some(k) => format!("{} [{}]", c.path.display(), k),
+                None => c.path.display().to_string(),
+            };
+            let losers: Vec<String> = c.losers.iter().map(|l| l.to_string()).collect();
+            let verb = match c.action {
+                ConflictAction::Replaced => "overrides",
+                ConflictAction::Removed => "removes",
+            };
+            writeln!(f, "{}: {} {} {}", item, c.winner, verb, losers.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_overrides_fold_into_one_conflict() {
+        let mut report = ConflictReport::default();
+        let path = PathBuf::from("game/map/provinces.yaml");
+        report.record(path.clone(), None, ConflictAction::Replaced, ContentSource::Game, ContentSource::Mod("a".into()));
+        report.record(path.clone(), None, ConflictAction::Replaced, ContentSource::Mod("a".into()), ContentSource::Mod("b".into()));
+        report.record(path.clone(), Some("3".into()), ConflictAction::Removed, ContentSource::Game, ContentSource::Mod("a".into()));
+
+        assert_eq!(report.conflicts.len(), 2);
+        let file = &report.conflicts[0];
+        assert_eq!(file.winner, ContentSource::Mod("b".into()));
+        assert_eq!(file.losers, vec![ContentSource::Game, ContentSource::Mod("a".into())]);
+        assert_eq!(report.between_mods().count(), 1);
+        assert_eq!(
+            report.to_string(),
+            "game/map/provinces.yaml: mod 'b' overrides game, mod 'a'\ngame/map/provinces.yaml [3]: mod 'a' removes game\n"
+        );
+    }
+}
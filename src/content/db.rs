@@ -0,0 +1,548 @@
+#[cfg(feature = "bevy")]
+use bevy::prelude::*;
+
+use anyhow::{Context, Result};
+use indexmap::IndexMap;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::content::diagnostics::{codes, yaml_error_location, Diagnostic, Location};
+use crate::content::loader::load_content;
+use crate::content::spans::SpanIndex;
+use crate::core::{
+    adjacency::Adjacency,
+    characters::{CharacterDef, CharacterFile, TraitDef, TraitFile},
+    country::CountryDef,
+    data::Edge,
+    events::{EventDef, EventFile},
+    ideas::{IdeaDef, IdeaFile},
+    politics::{IdeologyDef, IdeologyFile, PoliticsRules},
+    focus::FocusTree,
+    province::{ProvinceDef, ProvincesList},
+    research::{CountryResearch, TechDef, Technologies},
+    scenario::ScenarioDef,
+    time::{GameDate, SpeedTable},
+    units::{BattalionDef, Battalions},
+};
+
+/// The kinds of content file the database understands, recognised by file name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContentKind {
+    Provinces,
+    Adjacency,
+    Country,
+    FocusTree,
+    Battalions,
+    Technologies,
+    Research,
+    Scenario,
+    Speeds,
+    Events,
+    Ideas,
+    Ideologies,
+    Characters,
+    Traits,
+}
+
+impl ContentKind {
+    pub const ALL: [ContentKind; 14] = [
+        ContentKind::Provinces,
+        ContentKind::Adjacency,
+        ContentKind::Country,
+        ContentKind::FocusTree,
+        ContentKind::Battalions,
+        ContentKind::Technologies,
+        ContentKind::Research,
+        ContentKind::Scenario,
+        ContentKind::Speeds,
+        ContentKind::Events,
+        ContentKind::Ideas,
+        ContentKind::Ideologies,
+        ContentKind::Characters,
+        ContentKind::Traits,
+    ];
+
+    /// Kinds whose files can have any name, recognised by their directory instead.
+    const BY_DIRECTORY: [ContentKind; 2] = [ContentKind::Events, ContentKind::Ideas];
+
+    pub fn of(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        let stem = name.strip_suffix(".yaml").or_else(|| name.strip_suffix(".yml"))?;
+        let dir = path.parent().and_then(|p| p.file_name()).and_then(|d| d.to_str());
+        if let Some(kind) = Self::BY_DIRECTORY.into_iter().find(|k| dir == Some(k.name())) {
+            return Some(kind);
+        }
+        Self::from_name(stem).filter(|k| !Self::BY_DIRECTORY.contains(k))
+    }
+
+    /// File stem of this kind of file, also used to name it in `validate.yaml`.
+    pub fn name(self) -> &'static str {
+        match self {
+            ContentKind::Provinces => "provinces",
+            ContentKind::Adjacency => "adjacency",
+            ContentKind::Country => "country",
+            ContentKind::FocusTree => "focus_tree",
+            ContentKind::Battalions => "battalions",
+            ContentKind::Technologies => "technologies",
+            ContentKind::Research => "research",
+            ContentKind::Scenario => "scenario",
+            ContentKind::Speeds => "speeds",
+            ContentKind::Events => "events",
+            ContentKind::Ideas => "ideas",
+            ContentKind::Ideologies => "ideologies",
+            ContentKind::Characters => "characters",
+            ContentKind::Traits => "traits",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.name() == name)
+    }
+}
+
+/// A key serde skipped because no field of the target type has that name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownField {
+    pub path: PathBuf,
+    pub kind: ContentKind,
+    /// Dotted path within the file, e.g. `battalions.0.weight`
+    pub field: String,
+}
+
+impl UnknownField {
+    /// The key itself, without its parents.
+    pub fn name(&self) -> &str {
+        self.field.rsplit('.').next().unwrap_or(&self.field)
+    }
+}
+
+/// Typed view of all loaded content, parsed once from the loader output.
+///
+/// Per-country files (`focus_tree.yaml`, `research.yaml`) are keyed by the name
+/// of the directory they live in, i.e. `game/countries/<TAG>/...`.
+#[cfg_attr(feature = "bevy", derive(Resource))]
+#[derive(Debug, Clone, Default)]
+pub struct ContentDb {
+    pub provinces: IndexMap<u32, ProvinceDef>,
+    pub edges: Vec<Edge>,
+    pub countries: IndexMap<String, CountryDef>,
+    pub focus_trees: IndexMap<String, FocusTree>,
+    pub battalions: IndexMap<String, BattalionDef>,
+    pub techs: IndexMap<String, TechDef>,
+    pub research: IndexMap<String, CountryResearch>,
+    pub scenario: Option<ScenarioDef>,
+    pub speeds: Option<SpeedTable>,
+    pub events: IndexMap<String, EventDef>,
+    pub ideas: IndexMap<String, IdeaDef>,
+    pub ideologies: IndexMap<String, IdeologyDef>,
+    /// Characters of every country by id; the country is the directory of their file
+    pub characters: IndexMap<String, CharacterDef>,
+    pub traits: IndexMap<String, TraitDef>,
+    /// Election and coup settings from `game/ideologies.yaml`
+    pub politics: PoliticsRules,
+    /// File each entity was read from, for diagnostics
+    pub province_files: HashMap<u32, PathBuf>,
+    pub country_files: HashMap<String, PathBuf>,
+    pub focus_tree_files: HashMap<String, PathBuf>,
+    pub battalion_files: HashMap<String, PathBuf>,
+    pub tech_files: HashMap<String, PathBuf>,
+    pub research_files: HashMap<String, PathBuf>,
+    pub scenario_file: Option<PathBuf>,
+    pub speeds_file: Option<PathBuf>,
+    pub event_files: HashMap<String, PathBuf>,
+    pub idea_files: HashMap<String, PathBuf>,
+    pub ideologies_file: Option<PathBuf>,
+    pub character_files: HashMap<String, PathBuf>,
+    pub traits_file: Option<PathBuf>,
+    /// File and position within its list of each entry in `edges`
+    pub edge_origins: Vec<(PathBuf, usize)>,
+    /// Source positions of every recognised file
+    pub spans: HashMap<PathBuf, SpanIndex>,
+    /// Parse failures and duplicate ids found while building
+    pub issues: Vec<Diagnostic>,
+    /// Keys that were dropped while parsing; reported in strict mode
+    pub unknown_fields: Vec<UnknownField>,
+    owners: HashMap<u32, String>,
+    neighbours: HashMap<u32, Vec<u32>>,
+}
+
+impl ContentDb {
+    /// Load `game/` plus mods under `root` and parse the result.
+    pub fn load(root: &Path) -> Result<Self> {
+        let content = load_content(root).with_context(|| format!("loading content from {}", root.display()))?;
+        Ok(Self::from_files(&content.files))
+    }
+
+    /// Parse every recognised file once. Problems are collected in `issues`
+    /// instead of aborting so the validator can report all of them at once.
+    pub fn from_files(files: &[(PathBuf, String)]) -> Self {
+        let mut db = ContentDb::default();
+        for (path, contents) in files {
+            let Some(kind) = ContentKind::of(path) else { continue };
+            db.spans.insert(path.clone(), SpanIndex::parse(contents));
+            match kind {
+                ContentKind::Provinces => {
+                    let Some(list) = db.parse::<ProvincesList>(kind, path, contents) else { continue };
+                    for p in list.provinces {
+                        if let Some(first) = db.province_files.get(&p.id) {
+                            let issue = Diagnostic::error(codes::DUPLICATE_PROVINCE, format!("Duplicate province ID {}", p.id));
+                            db.issues.push(db.duplicate(issue, ("provinces", "id", &p.id.to_string()), path, first));
+                            continue;
+                        }
+                        db.province_files.insert(p.id, path.clone());
+                        db.provinces.insert(p.id, p);
+                    }
+                }
+                ContentKind::Adjacency => {
+                    if let Some(adj) = db.parse::<Adjacency>(kind, path, contents) {
+                        db.edge_origins.extend((0..adj.edges.len()).map(|i| (path.clone(), i)));
+                        db.edges.extend(adj.edges);
+                    }
+                }
+                ContentKind::Country => {
+                    let Some(def) = db.parse::<CountryDef>(kind, path, contents) else { continue };
+                    if let Some(prev) = db.country_files.get(&def.tag) {
+                        let msg = format!("Country tag {} is defined in both {} and {}", def.tag, prev.display(), path.display());
+                        let issue = Diagnostic::error(codes::DUPLICATE_COUNTRY, msg)
+                            .at(db.locate(path, "tag").with_label("defined again here"))
+                            .related(db.locate(prev, "tag").with_label("first defined here"));
+                        db.issues.push(issue);
+                        continue;
+                    }
+                    db.country_files.insert(def.tag.clone(), path.clone());
+                    db.countries.insert(def.tag.clone(), def);
+                }
+                ContentKind::FocusTree => {
+                    let Some(tree) = db.parse::<FocusTree>(kind, path, contents) else { continue };
+                    let tag = owner_dir(path);
+                    db.focus_tree_files.insert(tag.clone(), path.clone());
+                    db.focus_trees.insert(tag, tree);
+                }
+                ContentKind::Battalions => {
+                    let Some(list) = db.parse::<Battalions>(kind, path, contents) else { continue };
+                    for b in list.battalions {
+                        if let Some(first) = db.battalion_files.get(&b.id) {
+                            let msg = format!("Battalions {} contains duplicate battalion id {}", path.display(), b.id);
+                            let issue = Diagnostic::error(codes::DUPLICATE_BATTALION, msg);
+                            db.issues.push(db.duplicate(issue, ("battalions", "id", &b.id), path, first));
+                            continue;
+                        }
+                        db.battalion_files.insert(b.id.clone(), path.clone());
+                        db.battalions.insert(b.id.clone(), b);
+                    }
+                }
+                ContentKind::Technologies => {
+                    let Some(list) = db.parse::<Technologies>(kind, path, contents) else { continue };
+                    for t in list.technologies {
+                        if let Some(first) = db.tech_files.get(&t.id) {
+                            let msg = format!("Technologies {} contains duplicate tech id {}", path.display(), t.id);
+                            let issue = Diagnostic::error(codes::DUPLICATE_TECH, msg);
+                            db.issues.push(db.duplicate(issue, ("technologies", "id", &t.id), path, first));
+                            continue;
+                        }
+                        db.tech_files.insert(t.id.clone(), path.clone());
+                        db.techs.insert(t.id.clone(), t);
+                    }
+                }
+                ContentKind::Research => {
+                    if let Some(r) = db.parse::<CountryResearch>(kind, path, contents) {
+                        db.research_files.insert(owner_dir(path), path.clone());
+                        db.research.insert(owner_dir(path), r);
+                    }
+                }
+                ContentKind::Scenario => {
+                    if let Some(scenario) = db.parse::<ScenarioDef>(kind, path, contents) {
+                        db.scenario_file = Some(path.clone());
+                        db.scenario = Some(scenario);
+                    }
+                }
+                ContentKind::Speeds => {
+                    if let Some(speeds) = db.parse::<SpeedTable>(kind, path, contents) {
+                        db.speeds_file = Some(path.clone());
+                        db.speeds = Some(speeds);
+                    }
+                }
+                ContentKind::Events => {
+                    let Some(file) = db.parse::<EventFile>(kind, path, contents) else { continue };
+                    for e in file.events {
+                        if let Some(first) = db.event_files.get(&e.id) {
+                            let msg = format!("Event id {} is defined more than once", e.id);
+                            let issue = Diagnostic::error(codes::DUPLICATE_EVENT, msg);
+                            db.issues.push(db.duplicate(issue, ("events", "id", &e.id), path, first));
+                            continue;
+                        }
+                        db.event_files.insert(e.id.clone(), path.clone());
+                        db.events.insert(e.id.clone(), e);
+                    }
+                }
+                ContentKind::Ideas => {
+                    let Some(file) = db.parse::<IdeaFile>(kind, path, contents) else { continue };
+                    for idea in file.ideas {
+                        if let Some(first) = db.idea_files.get(&idea.id) {
+                            let msg = format!("Idea id {} is defined more than once", idea.id);
+                            let issue = Diagnostic::error(codes::DUPLICATE_IDEA, msg);
+                            db.issues.push(db.duplicate(issue, ("ideas", "id", &idea.id), path, first));
+                            continue;
+                        }
+                        db.idea_files.insert(idea.id.clone(), path.clone());
+                        db.ideas.insert(idea.id.clone(), idea);
+                    }
+                }
+                ContentKind::Ideologies => {
+                    let Some(file) = db.parse::<IdeologyFile>(kind, path, contents) else { continue };
+                    db.ideologies_file = Some(path.clone());
+                    db.politics = file.rules;
+                    for ideology in file.ideologies {
+                        if db.ideologies.contains_key(&ideology.id) {
+                            let msg = format!("Ideology id {} is defined more than once", ideology.id);
+                            let issue = Diagnostic::error(codes::DUPLICATE_IDEOLOGY, msg);
+                            db.issues.push(db.duplicate(issue, ("ideologies", "id", &ideology.id), path, path));
+                            continue;
+                        }
+                        db.ideologies.insert(ideology.id.clone(), ideology);
+                    }
+                }
+                ContentKind::Characters => {
+                    let Some(file) = db.parse::<CharacterFile>(kind, path, contents) else { continue };
+                    for c in file.characters {
+                        if let Some(first) = db.character_files.get(&c.id) {
+                            let msg = format!("Character id {} is defined more than once", c.id);
+                            let issue = Diagnostic::error(codes::DUPLICATE_CHARACTER, msg);
+                            db.issues.push(db.duplicate(issue, ("characters", "id", &c.id), path, first));
+                            continue;
+                        }
+                        db.character_files.insert(c.id.clone(), path.clone());
+                        db.characters.insert(c.id.clone(), c);
+                    }
+                }
+                ContentKind::Traits => {
+                    let Some(file) = db.parse::<TraitFile>(kind, path, contents) else { continue };
+                    db.traits_file = Some(path.clone());
+                    for t in file.traits {
+                        if db.traits.contains_key(&t.id) {
+                            let msg = format!("Trait id {} is defined more than once", t.id);
+                            let issue = Diagnostic::error(codes::DUPLICATE_TRAIT, msg);
+                            db.issues.push(db.duplicate(issue, ("traits", "id", &t.id), path, path));
+                            continue;
+                        }
+                        db.traits.insert(t.id.clone(), t);
+                    }
+                }
+            }
+        }
+        db.build_indexes();
+        db
+    }
+
+    fn parse<T: DeserializeOwned>(&mut self, kind: ContentKind, path: &Path, contents: &str) -> Option<T> {
+        let mut unknown = Vec::new();
+        let parsed = serde_ignored::deserialize(serde_yaml::Deserializer::from_str(contents), |field| {
+            let mut segments = Vec::new();
+            field_segments(&field, &mut segments);
+            unknown.push(segments.join("."));
+        });
+        match parsed {
+            Ok(v) => {
+                let fields = unknown.into_iter().map(|field| UnknownField { path: path.to_path_buf(), kind, field });
+                self.unknown_fields.extend(fields);
+                Some(v)
+            }
+            Err(e) => {
+                let issue = Diagnostic::error(codes::PARSE_FAILED, format!("parsing {}: {}", path.display(), e))
+                    .at(yaml_error_location(path, &e));
+                self.issues.push(issue);
+                None
+            }
+        }
+    }
+
+    /// Point `issue` at the second definition of an id in `path` and add the
+    /// first definition in `first` as a related location.
+    fn duplicate(&self, issue: Diagnostic, (list, field, id): (&str, &str, &str), path: &Path, first: &Path) -> Diagnostic {
+        let entries = |p: &Path| self.spans.get(p).map(|s| s.entries(list, field, id)).unwrap_or_default();
+        let here = entries(path);
+        let again = if path == first { here.get(1) } else { here.first() };
+        let location = match again {
+            Some(entry) => self.locate(path, &format!("{}.{}", entry, field)),
+            None => Location::file(path),
+        };
+        let original = match entries(first).first() {
+            Some(entry) => self.locate(first, &format!("{}.{}", entry, field)),
+            None => Location::file(first),
+        };
+        issue.at(location.with_label("defined again here")).related(original.with_label("first defined here"))
+    }
+
+    /// Location of the value at `yaml_path` (see [`SpanIndex`]) in `path`,
+    /// or just the file when the value can't be found.
+    pub fn locate(&self, path: &Path, yaml_path: &str) -> Location {
+        match self.spans.get(path).and_then(|s| s.value(yaml_path)) {
+            Some(span) => Location::span(path, span),
+            None => Location::file(path),
+        }
+    }
+
+    /// Like [`ContentDb::locate`], but points at the key naming the value.
+    pub fn locate_key(&self, path: &Path, yaml_path: &str) -> Location {
+        match self.spans.get(path).and_then(|s| s.key(yaml_path)) {
+            Some(span) => Location::span(path, span),
+            None => Location::file(path),
+        }
+    }
+
+    fn build_indexes(&mut self) {
+        for (tag, c) in &self.countries {
+            for id in c.owned_provinces.iter().flatten() {
+                // First owner in load order wins; the validator reports contested provinces
+                self.owners.entry(*id).or_insert_with(|| tag.clone());
+            }
+        }
+        for e in &self.edges {
+            self.neighbours.entry(e.a).or_default().push(e.b);
+            if e.a != e.b {
+                self.neighbours.entry(e.b).or_default().push(e.a);
+            }
+        }
+    }
+
+    pub fn province(&self, id: u32) -> Option<&ProvinceDef> {
+        self.provinces.get(&id)
+    }
+
+    pub fn country(&self, tag: &str) -> Option<&CountryDef> {
+        self.countries.get(tag)
+    }
+
+    pub fn focus_tree(&self, tag: &str) -> Option<&FocusTree> {
+        self.focus_trees.get(tag)
+    }
+
+    pub fn battalion(&self, id: &str) -> Option<&BattalionDef> {
+        self.battalions.get(id)
+    }
+
+    pub fn tech(&self, id: &str) -> Option<&TechDef> {
+        self.techs.get(id)
+    }
+
+    pub fn event(&self, id: &str) -> Option<&EventDef> {
+        self.events.get(id)
+    }
+
+    pub fn idea(&self, id: &str) -> Option<&IdeaDef> {
+        self.ideas.get(id)
+    }
+
+    pub fn ideology(&self, id: &str) -> Option<&IdeologyDef> {
+        self.ideologies.get(id)
+    }
+
+    pub fn character(&self, id: &str) -> Option<&CharacterDef> {
+        self.characters.get(id)
+    }
+
+    /// Country whose `characters.yaml` defines the character.
+    pub fn character_owner(&self, id: &str) -> Option<String> {
+        self.character_files.get(id).map(|p| owner_dir(p))
+    }
+
+    /// Characters defined for `tag`, in file order.
+    pub fn characters_of<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = &'a CharacterDef> + 'a {
+        self.characters.values().filter(move |c| self.character_files.get(&c.id).is_some_and(|p| owner_dir(p) == tag))
+    }
+
+    pub fn character_trait(&self, id: &str) -> Option<&TraitDef> {
+        self.traits.get(id)
+    }
+
+    /// Whether governments of `ideology` hold elections.
+    pub fn holds_elections(&self, ideology: &str) -> bool {
+        self.ideology(ideology).is_some_and(|i| i.elections)
+    }
+
+    /// Start date of the loaded scenario, or the default 1936-01-01.
+    pub fn start_date(&self) -> GameDate {
+        self.scenario.as_ref().map(|s| s.start_date).unwrap_or_default()
+    }
+
+    /// Game speeds from `game/speeds.yaml`, or the built-in table.
+    pub fn speed_table(&self) -> SpeedTable {
+        self.speeds.clone().unwrap_or_default()
+    }
+
+    /// Country whose `owned_provinces` lists the province.
+    pub fn owner_of(&self, province: u32) -> Option<&CountryDef> {
+        self.owners.get(&province).and_then(|tag| self.countries.get(tag))
+    }
+
+    /// Provinces sharing an adjacency edge with `province`.
+    pub fn neighbours(&self, province: u32) -> &[u32] {
+        self.neighbours.get(&province).map(Vec::as_slice).unwrap_or(&[])
+    }
+}
+
+/// Dotted segments of a skipped key; `Option`/newtype wrappers add no segment.
+fn field_segments(path: &serde_ignored::Path, out: &mut Vec<String>) {
+    use serde_ignored::Path as P;
+    match path {
+        P::Root => {}
+        P::Seq { parent, index } => {
+            field_segments(parent, out);
+            out.push(index.to_string());
+        }
+        P::Map { parent, key } => {
+            field_segments(parent, out);
+            out.push(key.clone());
+        }
+        P::Some { parent } | P::NewtypeStruct { parent } | P::NewtypeVariant { parent } => field_segments(parent, out),
+    }
+}
+
+/// Name of the directory containing a per-country file (`GER` for
+/// `game/countries/GER/focus_tree.yaml`).
+pub(crate) fn owner_dir(path: &Path) -> String {
+    path.parent()
+        .and_then(|p| p.file_name())
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_typed_maps_and_indexes_from_files() {
+        let files = vec![
+            (PathBuf::from("game/map/provinces.yaml"),
+             "provinces:\n  - { id: 0, name: A, pos: { x: 0, y: 0 } }\n  - { id: 1, name: B, pos: { x: 1, y: 0 } }\n  - { id: 1, name: Dup, pos: { x: 2, y: 0 } }\n".to_string()),
+            (PathBuf::from("game/map/adjacency.yaml"), "edges:\n  - { a: 0, b: 1 }\n".to_string()),
+            (PathBuf::from("game/countries/GER/country.yaml"),
+             "tag: GER\nname: Germany\nowned_provinces: [1]\n".to_string()),
+            (PathBuf::from("game/countries/GER/focus_tree.yaml"),
+             "focuses:\n  - { id: a, name: A, days: 10 }\n".to_string()),
+            (PathBuf::from("game/units/battalions.yaml"), "battalions: [oops".to_string()),
+        ];
+        let db = ContentDb::from_files(&files);
+
+        assert_eq!(db.provinces.len(), 2);
+        assert_eq!(db.province(1).map(|p| p.name.as_str()), Some("B"));
+        assert_eq!(db.owner_of(1).map(|c| c.tag.as_str()), Some("GER"));
+        assert!(db.owner_of(0).is_none());
+        assert_eq!(db.neighbours(0), &[1]);
+        assert!(db.focus_tree("GER").and_then(|t| t.find("a")).is_some());
+
+        let messages: Vec<&str> = db.issues.iter().map(|i| i.message.as_str()).collect();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0], "Duplicate province ID 1");
+        assert!(messages[1].starts_with("parsing game/units/battalions.yaml"));
+        assert_eq!(db.issues[0].code, codes::DUPLICATE_PROVINCE);
+        // Both definitions of province 1 are located, down to the id value
+        let dup = &db.issues[0];
+        let (again, first) = (dup.location.as_ref().unwrap(), &dup.related[0]);
+        assert_eq!((again.line, again.column, again.end_column), (Some(4), Some(11), Some(12)));
+        assert_eq!((first.line, first.label.as_deref()), (Some(3), Some("first defined here")));
+        assert_eq!(db.issues[1].location.as_ref().and_then(|l| l.line), Some(1));
+    }
+}
@@ -0,0 +1,463 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::content::spans::Span;
+
+/// Stable diagnostic codes. Codes are never reused or renumbered so editors
+/// and CI can filter on them; add new checks with new codes.
+pub mod codes {
+    pub const LOAD_FAILED: &str = "PAN001";
+    pub const PARSE_FAILED: &str = "PAN002";
+    pub const SCHEMA_VIOLATION: &str = "PAN003";
+    pub const SCHEMA_UNREADABLE: &str = "PAN004";
+    pub const SCHEMA_MISSING: &str = "PAN005";
+    pub const UNKNOWN_FIELD: &str = "PAN006";
+
+    pub const DUPLICATE_PROVINCE: &str = "PAN010";
+    pub const DUPLICATE_COUNTRY: &str = "PAN011";
+    pub const DUPLICATE_BATTALION: &str = "PAN012";
+    pub const DUPLICATE_TECH: &str = "PAN013";
+
+    pub const EDGE_UNKNOWN_PROVINCE: &str = "PAN020";
+    pub const EDGE_SELF_LOOP: &str = "PAN021";
+    pub const DUPLICATE_EDGE: &str = "PAN022";
+    pub const MAP_ISLAND: &str = "PAN023";
+    pub const PROVINCE_WITHOUT_EDGES: &str = "PAN024";
+    pub const SHARED_POSITION: &str = "PAN025";
+
+    pub const OWNED_UNKNOWN_PROVINCE: &str = "PAN030";
+    pub const CONTROLLED_UNKNOWN_PROVINCE: &str = "PAN031";
+    pub const CAPITAL_UNKNOWN_PROVINCE: &str = "PAN032";
+    pub const CONTESTED_PROVINCE: &str = "PAN033";
+    pub const UNOWNED_PROVINCE: &str = "PAN034";
+    pub const OWNED_UNOWNED_PROVINCE: &str = "PAN035";
+    pub const CAPITAL_NOT_OWNED: &str = "PAN036";
+    pub const CONTROLLED_UNOWNED_PROVINCE: &str = "PAN037";
+    pub const INVALID_COUNTRY_TAG: &str = "PAN038";
+    pub const COUNTRY_TAG_MISMATCH: &str = "PAN039";
+
+    pub const DUPLICATE_FOCUS: &str = "PAN040";
+    pub const FOCUS_UNKNOWN_PREREQUISITE: &str = "PAN041";
+    pub const FOCUS_CYCLE: &str = "PAN042";
+    pub const FOCUS_UNREACHABLE: &str = "PAN043";
+    pub const FOCUS_ASYMMETRIC_EXCLUSION: &str = "PAN044";
+    pub const FOCUS_UNKNOWN_EXCLUSION: &str = "PAN045";
+
+    pub const TECH_UNKNOWN_PREREQUISITE: &str = "PAN050";
+    pub const TECH_CYCLE: &str = "PAN051";
+    pub const RESEARCH_UNKNOWN_TECH: &str = "PAN052";
+
+    pub const INVALID_SPEED: &str = "PAN060";
+
+    pub const DUPLICATE_EVENT: &str = "PAN070";
+    pub const EVENT_WITHOUT_SCHEDULE: &str = "PAN071";
+    pub const UNKNOWN_EFFECT: &str = "PAN072";
+    pub const TRIGGER_UNKNOWN_REFERENCE: &str = "PAN073";
+    pub const SCRIPT_VALUE_NEVER_SET: &str = "PAN074";
+    pub const INVALID_EFFECT_PARAMS: &str = "PAN075";
+
+    pub const DUPLICATE_IDEA: &str = "PAN080";
+    pub const UNKNOWN_MODIFIER_KEY: &str = "PAN081";
+    pub const MULTIPLY_ON_ZERO_BASE: &str = "PAN082";
+    pub const IDEA_UNKNOWN_UNIT: &str = "PAN083";
+
+    pub const DUPLICATE_IDEOLOGY: &str = "PAN090";
+    pub const UNKNOWN_IDEOLOGY: &str = "PAN091";
+
+    pub const DUPLICATE_CHARACTER: &str = "PAN100";
+    pub const DUPLICATE_TRAIT: &str = "PAN101";
+    pub const CHARACTER_UNKNOWN_IDEOLOGY: &str = "PAN102";
+    pub const COUNTRY_WITHOUT_LEADER: &str = "PAN103";
+    pub const CHARACTER_UNKNOWN_TRAIT: &str = "PAN104";
+    pub const CHARACTER_UNKNOWN_MODIFIER_KEY: &str = "PAN105";
+    pub const TRAIT_NOT_BATTALION_STAT: &str = "PAN106";
+
+    /// Every code with a one-line description, used for SARIF rule metadata.
+    pub const ALL: &[(&str, &str)] = &[
+        (LOAD_FAILED, "Content could not be loaded"),
+        (PARSE_FAILED, "Content file could not be parsed"),
+        (SCHEMA_VIOLATION, "Content file does not match its JSON schema"),
+        (SCHEMA_UNREADABLE, "JSON schema could not be read"),
+        (SCHEMA_MISSING, "No schema covers a content file"),
+        (UNKNOWN_FIELD, "Content file has a field the game does not read"),
+        (DUPLICATE_PROVINCE, "Province id defined more than once"),
+        (DUPLICATE_COUNTRY, "Country tag defined more than once"),
+        (DUPLICATE_BATTALION, "Battalion id defined more than once"),
+        (DUPLICATE_TECH, "Technology id defined more than once"),
+        (EDGE_UNKNOWN_PROVINCE, "Adjacency edge references a missing province"),
+        (EDGE_SELF_LOOP, "Adjacency edge connects a province to itself"),
+        (DUPLICATE_EDGE, "Adjacency edge listed more than once, in either direction"),
+        (MAP_ISLAND, "Provinces unreachable from the rest of the map and not marked as islands"),
+        (PROVINCE_WITHOUT_EDGES, "Province has no adjacency edges"),
+        (SHARED_POSITION, "Two provinces share the same map position"),
+        (OWNED_UNKNOWN_PROVINCE, "Country owns an unknown province"),
+        (CONTROLLED_UNKNOWN_PROVINCE, "Country controls an unknown province"),
+        (CAPITAL_UNKNOWN_PROVINCE, "Country capital is an unknown province"),
+        (CONTESTED_PROVINCE, "Province is owned by more than one country"),
+        (UNOWNED_PROVINCE, "Province has no owner and is not marked unowned"),
+        (OWNED_UNOWNED_PROVINCE, "Province marked unowned is owned by a country"),
+        (CAPITAL_NOT_OWNED, "Country capital is outside its own territory"),
+        (CONTROLLED_UNOWNED_PROVINCE, "Country controls a province no country owns"),
+        (INVALID_COUNTRY_TAG, "Country tag is not three uppercase letters"),
+        (COUNTRY_TAG_MISMATCH, "Country tag differs from its directory name"),
+        (DUPLICATE_FOCUS, "Focus tree contains duplicate focus ids"),
+        (FOCUS_UNKNOWN_PREREQUISITE, "Focus has an unknown prerequisite"),
+        (FOCUS_CYCLE, "Focus tree prerequisites form a cycle"),
+        (FOCUS_UNREACHABLE, "Focus requires two mutually exclusive focuses and can never be taken"),
+        (FOCUS_ASYMMETRIC_EXCLUSION, "Mutual exclusion is declared on only one of the two focuses"),
+        (FOCUS_UNKNOWN_EXCLUSION, "Focus is mutually exclusive with an unknown focus"),
+        (TECH_UNKNOWN_PREREQUISITE, "Technology has an unknown prerequisite"),
+        (TECH_CYCLE, "Technology prerequisites form a cycle"),
+        (RESEARCH_UNKNOWN_TECH, "Research queue references an unknown technology"),
+        (INVALID_SPEED, "Game speed is not a positive number of seconds per day"),
+        (DUPLICATE_EVENT, "Event id is defined more than once"),
+        (EVENT_WITHOUT_SCHEDULE, "Event needs exactly one of date or mean_time_to_happen"),
+        (UNKNOWN_EFFECT, "Effect type is not registered"),
+        (TRIGGER_UNKNOWN_REFERENCE, "Trigger references an unknown country, focus, technology, idea or ideology"),
+        (SCRIPT_VALUE_NEVER_SET, "Trigger reads a flag or variable that no effect sets"),
+        (INVALID_EFFECT_PARAMS, "Effect parameters are missing or reference unknown content"),
+        (DUPLICATE_IDEA, "Idea id is defined more than once"),
+        (UNKNOWN_MODIFIER_KEY, "Idea modifier key is not read by the simulation"),
+        (MULTIPLY_ON_ZERO_BASE, "Multiplicative modifier on a value that starts at zero, which it never changes"),
+        (IDEA_UNKNOWN_UNIT, "Idea modifier names an unknown unit"),
+        (DUPLICATE_IDEOLOGY, "Ideology id is defined more than once"),
+        (UNKNOWN_IDEOLOGY, "Country ideology or popularity names an unknown ideology"),
+        (DUPLICATE_CHARACTER, "Character id is defined more than once"),
+        (DUPLICATE_TRAIT, "Trait id is defined more than once"),
+        (CHARACTER_UNKNOWN_IDEOLOGY, "Character leads an unknown ideology"),
+        (COUNTRY_WITHOUT_LEADER, "Country has characters but no leader for its ruling ideology"),
+        (CHARACTER_UNKNOWN_TRAIT, "Character has an unknown trait"),
+        (CHARACTER_UNKNOWN_MODIFIER_KEY, "Character modifier key is not read by the simulation"),
+        (TRAIT_NOT_BATTALION_STAT, "Trait modifier is not a battalion stat"),
+    ];
+
+    pub fn describe(code: &str) -> Option<&'static str> {
+        ALL.iter().find(|(c, _)| *c == code).map(|(_, d)| *d)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+        })
+    }
+}
+
+impl Severity {
+    /// SARIF `level` for this severity.
+    pub fn sarif_level(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "note",
+        }
+    }
+}
+
+/// How many diagnostics of each severity a run produced.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Summary {
+    pub errors: usize,
+    pub warnings: usize,
+    pub infos: usize,
+    /// Dropped by `off` rules or suppression comments
+    pub suppressed: usize,
+}
+
+impl Summary {
+    pub fn of(diagnostics: &[Diagnostic], suppressed: usize) -> Self {
+        let count = |s| diagnostics.iter().filter(|d| d.severity == s).count();
+        Summary { errors: count(Severity::Error), warnings: count(Severity::Warning), infos: count(Severity::Info), suppressed }
+    }
+
+    /// Whether CI should fail: any error, or any warning under `--deny-warnings`.
+    pub fn fails(&self, deny_warnings: bool) -> bool {
+        self.errors > 0 || (deny_warnings && self.warnings > 0)
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} error(s), {} warning(s), {} info", self.errors, self.warnings, self.infos)?;
+        if self.suppressed > 0 {
+            write!(f, ", {} suppressed", self.suppressed)?;
+        }
+        Ok(())
+    }
+}
+
+/// A file and, where known, a 1-based line and column range in it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Location {
+    pub path: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    /// Column just past the end of the offending value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_column: Option<usize>,
+    /// Short text printed under the carets, e.g. "first defined here"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+impl Location {
+    pub fn file(path: &Path) -> Self {
+        Location { path: path.to_path_buf(), line: None, column: None, end_column: None, label: None }
+    }
+
+    pub fn at(path: &Path, line: usize, column: usize) -> Self {
+        Location { line: Some(line), column: Some(column), ..Location::file(path) }
+    }
+
+    pub fn span(path: &Path, span: Span) -> Self {
+        Location { end_column: Some(span.column + span.len), ..Location::at(path, span.line, span.column) }
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+            if let Some(col) = self.column {
+                write!(f, ":{}", col)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One problem found in content, with a stable code and where it was found.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: &'static str,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<Location>,
+    /// Other places involved, e.g. the first definition of a duplicate
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub related: Vec<Location>,
+}
+
+impl Diagnostic {
+    pub fn error(code: &'static str, message: impl Into<String>) -> Self {
+        Diagnostic { severity: Severity::Error, code, message: message.into(), location: None, related: Vec::new() }
+    }
+
+    /// Worth a look, but does not fail validation.
+    pub fn warning(code: &'static str, message: impl Into<String>) -> Self {
+        Diagnostic { severity: Severity::Warning, ..Diagnostic::error(code, message) }
+    }
+
+    pub fn at(mut self, location: Location) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    pub fn in_file(self, path: &Path) -> Self {
+        self.at(Location::file(path))
+    }
+
+    pub fn related(mut self, location: Location) -> Self {
+        self.related.push(location);
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// Render rustc-style, quoting the offending lines from `files` with
+    /// carets under each located value.
+    pub fn render(&self, files: &[(PathBuf, String)]) -> String {
+        use std::fmt::Write;
+        let source = |path: &Path| files.iter().find(|(p, _)| p == path).map(|(_, s)| s.as_str());
+        let locations = self.location.iter().chain(&self.related);
+        let width = locations.clone().filter_map(|l| l.line).max().unwrap_or(0).to_string().len();
+        let pad = " ".repeat(width);
+
+        let mut out = String::new();
+        let mut lines = self.message.trim_end().lines();
+        writeln!(out, "{}[{}]: {}", self.severity, self.code, lines.next().unwrap_or("")).unwrap();
+        for extra in lines {
+            writeln!(out, "{} = {}", pad, extra).unwrap();
+        }
+        for (i, loc) in locations.enumerate() {
+            // rustc marks the primary location with --> and the others with :::
+            writeln!(out, "{}{} {}", pad, if i == 0 { "-->" } else { ":::" }, loc).unwrap();
+            let quoted = loc.line.zip(source(&loc.path)).and_then(|(l, s)| Some((l, s.lines().nth(l.checked_sub(1)?)?)));
+            let Some((line, text)) = quoted else {
+                if let Some(label) = &loc.label {
+                    writeln!(out, "{} = {}", pad, label).unwrap();
+                }
+                continue;
+            };
+            let column = loc.column.unwrap_or(1);
+            let carets = loc.end_column.map_or(1, |end| end.saturating_sub(column).max(1));
+            writeln!(out, "{} |", pad).unwrap();
+            writeln!(out, "{:>width$} | {}", line, text, width = width).unwrap();
+            // Keep tabs so the carets line up with the quoted text
+            let indent: String = text.chars().take(column.saturating_sub(1)).map(|c| if c == '\t' { '\t' } else { ' ' }).collect();
+            let label = loc.label.as_deref().map(|l| format!(" {}", l)).unwrap_or_default();
+            writeln!(out, "{} | {}{}{}", pad, indent, "^".repeat(carets), label).unwrap();
+        }
+        out
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]: {}", self.severity, self.code, self.message.trim_end())?;
+        if let Some(loc) = &self.location {
+            write!(f, "\n  --> {}", loc)?;
+        }
+        for loc in &self.related {
+            write!(f, "\n  note: see also {}", loc)?;
+        }
+        Ok(())
+    }
+}
+
+/// Location of a YAML parse error, if the parser reported one.
+pub fn yaml_error_location(path: &Path, err: &serde_yaml::Error) -> Location {
+    match err.location() {
+        Some(l) => Location::at(path, l.line(), l.column()),
+        None => Location::file(path),
+    }
+}
+
+/// Diagnostics as a JSON document: `{ "version": 1, "summary": {...}, "diagnostics": [...] }`.
+pub fn to_json(diagnostics: &[Diagnostic], summary: &Summary) -> Value {
+    json!({ "version": 1, "summary": summary, "diagnostics": diagnostics })
+}
+
+/// Diagnostics as a SARIF 2.1.0 log with one run.
+pub fn to_sarif(diagnostics: &[Diagnostic]) -> Value {
+    let rules: Vec<Value> = codes::ALL
+        .iter()
+        .map(|(id, text)| json!({ "id": id, "shortDescription": { "text": text } }))
+        .collect();
+    let results: Vec<Value> = diagnostics
+        .iter()
+        .map(|d| {
+            let mut result = json!({
+                "ruleId": d.code,
+                "level": d.severity.sarif_level(),
+                "message": { "text": d.message.trim_end() },
+            });
+            if let Some(loc) = &d.location {
+                result["locations"] = json!([sarif_location(loc)]);
+            }
+            if !d.related.is_empty() {
+                result["relatedLocations"] = d.related.iter().map(sarif_location).collect();
+            }
+            result
+        })
+        .collect();
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": { "driver": {
+                "name": "panopticon-validate",
+                "version": env!("CARGO_PKG_VERSION"),
+                "rules": rules,
+            }},
+            "results": results,
+        }],
+    })
+}
+
+fn sarif_location(loc: &Location) -> Value {
+    // SARIF wants URIs, so always use forward slashes
+    let uri = loc.path.to_string_lossy().replace('\\', "/");
+    let mut physical = json!({ "artifactLocation": { "uri": uri } });
+    if let Some(line) = loc.line {
+        physical["region"] = json!({ "startLine": line });
+        if let Some(col) = loc.column {
+            physical["region"]["startColumn"] = json!(col);
+        }
+        if let Some(end) = loc.end_column {
+            physical["region"]["endColumn"] = json!(end);
+        }
+    }
+    let mut location = json!({ "physicalLocation": physical });
+    if let Some(label) = &loc.label {
+        location["message"] = json!({ "text": label });
+    }
+    location
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::db::ContentDb;
+    use crate::content::loader::load_content;
+    use crate::content::validator::structural_diagnostics;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn sarif_output_points_at_the_files_on_disk() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let root = dir.path();
+        fs::create_dir_all(root.join("game/map"))?;
+        fs::write(
+            root.join("game/map/provinces.yaml"),
+            "provinces:\n  - { id: 0, name: A, pos: { x: 0, y: 0 }, unowned: true }\n  - { id: 1, name: B, pos: { x: 0, y: 0 }, unowned: true }\n",
+        )?;
+        fs::write(root.join("game/map/adjacency.yaml"), "edges:\n  - { a: 0, b: 1 }\n")?;
+        fs::create_dir_all(root.join("mods/a/game/map"))?;
+        fs::write(root.join("mods/a/game/map/adjacency.yaml"), "# More links\nedges:\n  add:\n    - { a: 1, b: 7 }\n")?;
+
+        let content = load_content(root)?;
+        let diags: Vec<Diagnostic> = structural_diagnostics(&ContentDb::from_files(&content.files))
+            .into_iter()
+            .map(|d| content.source_map.relocate(d))
+            .collect();
+        let sarif = to_sarif(&diags);
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        let result = |code: &str| results.iter().find(|r| r["ruleId"] == code).unwrap();
+
+        // The edge came from the mod's patch, not the merged file
+        let edge = result(codes::EDGE_UNKNOWN_PROVINCE);
+        assert_eq!(edge["level"], "error");
+        let physical = &edge["locations"][0]["physicalLocation"];
+        assert_eq!(physical["artifactLocation"]["uri"], "mods/a/game/map/adjacency.yaml");
+        assert_eq!(physical["region"]["startLine"], 4);
+        assert_eq!(physical["region"]["startColumn"], 18);
+        let rendered = diags.iter().find(|d| d.code == codes::EDGE_UNKNOWN_PROVINCE).unwrap().render(&content.sources);
+        assert!(rendered.contains("4 |     - { a: 1, b: 7 }"), "{}", rendered);
+
+        let overlap = result(codes::SHARED_POSITION);
+        assert_eq!(overlap["level"], "warning");
+        assert_eq!(overlap["locations"][0]["physicalLocation"]["artifactLocation"]["uri"], "game/map/provinces.yaml");
+
+        let json = to_json(&diags, &Summary::of(&diags, 0));
+        assert_eq!(json["summary"]["errors"], 1);
+        assert_eq!(json["diagnostics"][0]["code"], "PAN020");
+        assert_eq!(json["diagnostics"][0]["location"]["line"], 4);
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::core::focus::{Focus, FocusTree};
+
+/// What it takes to complete one focus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FocusPath {
+    pub id: String,
+    /// Every focus that has to be completed first, in an order satisfying
+    /// prerequisites, ending with this one
+    pub path: Vec<String>,
+    /// Fewest days until this focus completes. A country runs one focus at a
+    /// time, so this is the sum of `days` along `path`.
+    pub min_days: u32,
+    /// Two focuses on `path` that exclude each other, so this one can never be taken
+    pub blocked_by: Option<(String, String)>,
+}
+
+/// Reachability, mutual exclusion and timing for one focus tree.
+#[derive(Debug, Clone, Default)]
+pub struct FocusAnalysis {
+    /// One entry per focus, in tree order
+    pub focuses: Vec<FocusPath>,
+    /// `(a, b)`: `a` lists `b` in `mutually_exclusive` but `b` does not list `a`
+    pub asymmetric_exclusions: Vec<(String, String)>,
+    /// `(a, b)`: `a` lists `b` in `mutually_exclusive` but the tree has no `b`
+    pub unknown_exclusions: Vec<(String, String)>,
+}
+
+impl FocusAnalysis {
+    /// Analyse `tree`. Unknown prerequisites and cycles are reported by the
+    /// structural checks; here they are skipped rather than followed.
+    pub fn of(tree: &FocusTree) -> Self {
+        let by_id: HashMap<&str, &Focus> = tree.focuses.iter().map(|f| (f.id.as_str(), f)).collect();
+
+        // Exclusion counts in both directions for reachability, whichever side declares it
+        let mut excludes: HashSet<(&str, &str)> = HashSet::new();
+        let mut analysis = FocusAnalysis::default();
+        for f in &tree.focuses {
+            for other in &f.mutually_exclusive {
+                match by_id.get(other.as_str()) {
+                    None => analysis.unknown_exclusions.push((f.id.clone(), other.clone())),
+                    Some(o) => {
+                        if !o.mutually_exclusive.contains(&f.id) {
+                            analysis.asymmetric_exclusions.push((f.id.clone(), other.clone()));
+                        }
+                        excludes.insert((f.id.as_str(), other.as_str()));
+                        excludes.insert((other.as_str(), f.id.as_str()));
+                    }
+                }
+            }
+        }
+
+        for f in &tree.focuses {
+            let mut path = Vec::new();
+            requirements(&by_id, &f.id, &mut HashSet::new(), &mut path);
+            let blocked_by = path.iter().enumerate().find_map(|(i, a)| {
+                let b = path[i + 1..].iter().find(|b| excludes.contains(&(*a, **b)))?;
+                Some((a.to_string(), b.to_string()))
+            });
+            analysis.focuses.push(FocusPath {
+                id: f.id.clone(),
+                min_days: path.iter().map(|id| by_id[id].days).sum(),
+                path: path.into_iter().map(str::to_string).collect(),
+                blocked_by,
+            });
+        }
+        analysis
+    }
+
+    pub fn get(&self, id: &str) -> Option<&FocusPath> {
+        self.focuses.iter().find(|f| f.id == id)
+    }
+
+    /// Focuses whose prerequisites include two mutually exclusive focuses.
+    pub fn unreachable(&self) -> impl Iterator<Item = &FocusPath> {
+        self.focuses.iter().filter(|f| f.blocked_by.is_some())
+    }
+}
+
+/// Depth-first walk of prerequisites, pushing each focus after everything it needs.
+fn requirements<'a>(by_id: &HashMap<&'a str, &'a Focus>, id: &str, seen: &mut HashSet<&'a str>, out: &mut Vec<&'a str>) {
+    let Some(f) = by_id.get(id) else { return };
+    if !seen.insert(f.id.as_str()) {
+        return;
+    }
+    for pre in &f.prerequisites {
+        requirements(by_id, pre, seen, out);
+    }
+    out.push(f.id.as_str());
+}
+
+impl fmt::Display for FocusAnalysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.focuses.iter().map(|p| p.id.len()).max().unwrap_or(0);
+        for p in &self.focuses {
+            match &p.blocked_by {
+                Some((a, b)) => writeln!(f, "  {:<width$}  unreachable: requires both {} and {}, which are mutually exclusive", p.id, a, b)?,
+                None if p.path.len() > 1 => writeln!(f, "  {:<width$}  {:>5} days  via {}", p.id, p.min_days, p.path.join(" -> "))?,
+                None => writeln!(f, "  {:<width$}  {:>5} days", p.id, p.min_days)?,
+            }
+        }
+        for (a, b) in &self.asymmetric_exclusions {
+            writeln!(f, "  note: {} excludes {}, but {} does not exclude {}", a, b, b, a)?;
+        }
+        for (a, b) in &self.unknown_exclusions {
+            writeln!(f, "  note: {} excludes unknown focus {}", a, b)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_dead_focuses_one_sided_exclusions_and_min_days() {
+        let tree: FocusTree = serde_yaml::from_str(
+            "focuses:
+  - { id: industry, name: I, days: 70 }
+  - { id: army, name: A, days: 35, prerequisites: [industry], mutually_exclusive: [navy] }
+  - { id: navy, name: N, days: 35, prerequisites: [industry], mutually_exclusive: [army] }
+  - { id: doctrine, name: D, days: 10, prerequisites: [army, navy] }
+  - { id: reform, name: R, days: 20, prerequisites: [army], mutually_exclusive: [industry, ghost] }
+",
+        )
+        .unwrap();
+        let analysis = FocusAnalysis::of(&tree);
+
+        let army = analysis.get("army").unwrap();
+        assert_eq!(army.path, vec!["industry", "army"]);
+        assert_eq!(army.min_days, 105);
+        assert_eq!(analysis.get("doctrine").unwrap().blocked_by, Some(("army".into(), "navy".into())));
+        // reform needs industry, which it excludes
+        let unreachable: Vec<&str> = analysis.unreachable().map(|p| p.id.as_str()).collect();
+        assert_eq!(unreachable, vec!["doctrine", "reform"]);
+        assert_eq!(analysis.asymmetric_exclusions, vec![("reform".to_string(), "industry".to_string())]);
+        assert_eq!(analysis.unknown_exclusions, vec![("reform".to_string(), "ghost".to_string())]);
+    }
+}
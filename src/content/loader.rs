@@ -1,62 +1,330 @@
 use anyhow::{Context, Result};
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
-/// Loads all YAML files from `game/` then overlays `/mods/*` (alphabetical)
-/// Returns vector of (relative_path, contents)
-pub fn load_game_and_mods(root: &Path) -> Result<Vec<(PathBuf, String)>> {
-    let mut files: Vec<(PathBuf, String)> = Vec::new();
+use crate::content::conflicts::{ConflictAction, ConflictReport};
+use crate::content::manifest::{resolve_load_order, ModManifest, MANIFEST_FILE};
+use crate::content::merge::{apply_patch, empty_list_doc, is_patch, keyed_entries, list_keys, mergeable_list_for, MergeableList, PatchOutcome};
+use crate::content::sources::SourceMap;
 
-    let game_dir = root.join("game");
-    if game_dir.exists() {
-        for entry in WalkDir::new(&game_dir).into_iter().filter_map(|e| e.ok()) {
-                if entry.file_type().is_file() {
-                    if let Some(ext) = entry.path().extension() {
-                        if ext == "yaml" || ext == "yml" || ext == "json" {
-                            let rel = entry.path().strip_prefix(root).unwrap_or(entry.path()).to_path_buf();
-                            let s = std::fs::read_to_string(entry.path())
-                                .with_context(|| format!("reading {}", entry.path().display()))?;
-                            files.push((rel, s));
-                        }
-                    }
-                }
-        }
-    }
-
-    // load mods (alphabetical) and append/override by path
+/// Reads the manifest of every directory under `mods/` and returns the mods in
+/// resolved load order. Directories without a `mod.yaml` are treated as mods
+/// whose id is the directory name.
+pub fn discover_mods(root: &Path) -> Result<Vec<ModManifest>> {
+    let mut mods = Vec::new();
     let mods_dir = root.join("mods");
     if mods_dir.exists() {
         let mut mod_dirs: Vec<_> = std::fs::read_dir(&mods_dir)?.filter_map(|e| e.ok()).collect();
         mod_dirs.sort_by_key(|e| e.file_name());
         for md in mod_dirs {
-            if md.path().is_dir() {
-                for entry in WalkDir::new(md.path()).into_iter().filter_map(|e| e.ok()) {
-                    if entry.file_type().is_file() {
-                        if let Some(ext) = entry.path().extension() {
-                            if ext == "yaml" || ext == "yml" || ext == "json" {
-                                // For mods, compute the path relative to the mod directory so that
-                                // mods/<modname>/game/path.yaml maps to game/path.yaml and can override.
-                                let rel = match entry.path().strip_prefix(md.path()) {
-                                    Ok(p) => p.to_path_buf(),
-                                    Err(_) => entry.path().strip_prefix(root).unwrap_or(entry.path()).to_path_buf(),
-                                };
-                                let s = std::fs::read_to_string(entry.path())
-                                    .with_context(|| format!("reading {}", entry.path().display()))?;
-                                // If same relative path exists, replace previous (mods override)
-                                if let Some(pos) = files.iter().position(|(p, _)| p == &rel) {
-                                    files[pos] = (rel, s);
-                                } else {
-                                    files.push((rel, s));
-                                }
-                            }
+            if !md.path().is_dir() {
+                continue;
+            }
+            let manifest_path = md.path().join(MANIFEST_FILE);
+            let manifest = if manifest_path.exists() {
+                let s = std::fs::read_to_string(&manifest_path)
+                    .with_context(|| format!("reading {}", manifest_path.display()))?;
+                let mut m: ModManifest = serde_yaml::from_str(&s)
+                    .with_context(|| format!("parsing {}", manifest_path.display()))?;
+                m.dir = md.path();
+                m
+            } else {
+                ModManifest::implicit(&md.path())
+            };
+            mods.push(manifest);
+        }
+    }
+    Ok(resolve_load_order(mods)?)
+}
+
+/// Where a content file or list entry came from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "kind", content = "id", rename_all = "snake_case")]
+pub enum ContentSource {
+    Game,
+    Mod(String),
+}
+
+impl fmt::Display for ContentSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContentSource::Game => write!(f, "game"),
+            ContentSource::Mod(id) => write!(f, "mod '{}'", id),
+        }
+    }
+}
+
+/// How a source contributed to a content file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileChange {
+    /// First definition of the path
+    Added,
+    /// Whole-file override of an earlier definition
+    Replaced,
+    /// Entry-level merge into an earlier definition
+    Patched,
+}
+
+/// One step in the history of a content file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileOrigin {
+    pub path: PathBuf,
+    pub source: ContentSource,
+    pub change: FileChange,
+}
+
+/// The source that last defined a keyed entry of a mergeable list file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EntryOrigin {
+    pub path: PathBuf,
+    pub key: String,
+    pub source: ContentSource,
+}
+
+/// Everything produced by [`load_content`].
+#[derive(Debug, Clone, Default)]
+pub struct LoadedContent {
+    /// Mods in the order they were applied
+    pub mods: Vec<ModManifest>,
+    /// Final (relative_path, contents) after overrides and merges
+    pub files: Vec<(PathBuf, String)>,
+    /// Every file as read from disk, keyed by its path under the content
+    /// root, e.g. `mods/a/game/map/provinces.yaml`
+    pub sources: Vec<(PathBuf, String)>,
+    /// Leads positions in `files` back to `sources`, for diagnostics
+    pub source_map: SourceMap,
+    /// Every source that touched each file, in load order
+    pub file_history: Vec<FileOrigin>,
+    /// Contributor of every entry in mergeable list files (provinces, focuses, battalions, ...)
+    pub entries: Vec<EntryOrigin>,
+    /// Files and entries that a later source overrode or removed
+    pub conflicts: ConflictReport,
+}
+
+impl LoadedContent {
+    /// Which source contributed the entry `key` (an id, or `a-b` for edges) of `path`.
+    pub fn entry_source(&self, path: &Path, key: &str) -> Option<&ContentSource> {
+        self.entries.iter().find(|e| e.path == path && e.key == key).map(|e| &e.source)
+    }
+
+    /// The sources that touched `path`, oldest first. The last one that
+    /// replaced (or first added) the file owns its non-merged content.
+    pub fn file_sources<'a>(&'a self, path: &'a Path) -> impl Iterator<Item = &'a FileOrigin> + 'a {
+        self.file_history.iter().filter(move |o| o.path == path)
+    }
+}
+
+/// Loads all YAML files from `game/` then overlays `/mods/*` in the load order
+/// resolved from their manifests (see [`discover_mods`]).
+///
+/// A mod file replaces the file at the same relative path, unless it is a
+/// patch for one of the [`MERGEABLE_LISTS`](crate::content::merge::MERGEABLE_LISTS), e.g.
+/// `provinces: { add: [...], replace: [...], remove: [3] }`, in which case it
+/// is merged entry by entry into what earlier sources provided.
+pub fn load_content(root: &Path) -> Result<LoadedContent> {
+    let mut files: Vec<(PathBuf, String)> = Vec::new();
+    let mut origins: IndexMap<PathBuf, IndexMap<String, ContentSource>> = IndexMap::new();
+    let mut file_history: Vec<FileOrigin> = Vec::new();
+    let mut conflicts = ConflictReport::default();
+    let mut sources: Vec<(PathBuf, String)> = Vec::new();
+    let mut tracker = SourceTracker::default();
+
+    for (rel, s) in read_content_files(&root.join("game"), root)? {
+        record_entries(&mut origins, &rel, &s, &ContentSource::Game);
+        tracker.whole(&rel, &rel, &s);
+        sources.push((rel.clone(), s.clone()));
+        file_history.push(FileOrigin { path: rel.clone(), source: ContentSource::Game, change: FileChange::Added });
+        files.push((rel, s));
+    }
+
+    let mods = discover_mods(root)?;
+    for md in &mods {
+        let source = ContentSource::Mod(md.id.clone());
+        // For mods, paths are relative to the mod directory so that
+        // mods/<modname>/game/path.yaml maps to game/path.yaml and can override.
+        for (rel, s) in read_content_files(&md.dir, &md.dir)? {
+            if rel == Path::new(MANIFEST_FILE) {
+                continue;
+            }
+            let on_disk = md.dir.strip_prefix(root).unwrap_or(&md.dir).join(&rel);
+            sources.push((on_disk.clone(), s.clone()));
+            let existing = files.iter().position(|(p, _)| p == &rel);
+
+            if let Some(spec) = mergeable_list_for(&rel) {
+                let patch = serde_yaml::from_str::<serde_yaml::Value>(&s).ok().filter(|d| is_patch(spec, d));
+                if let Some(patch) = patch {
+                    let mut base = match existing {
+                        Some(pos) => serde_yaml::from_str(&files[pos].1)
+                            .with_context(|| format!("parsing {} before applying mod '{}'", rel.display(), md.id))?,
+                        None => empty_list_doc(spec),
+                    };
+                    let outcome = apply_patch(spec, &mut base, &patch)
+                        .with_context(|| format!("applying {} from mod '{}'", rel.display(), md.id))?;
+                    let merged = serde_yaml::to_string(&base)?;
+                    match existing {
+                        Some(pos) => files[pos].1 = merged,
+                        None => files.push((rel.clone(), merged)),
+                    }
+                    let change = if existing.is_some() { FileChange::Patched } else { FileChange::Added };
+                    file_history.push(FileOrigin { path: rel.clone(), source: source.clone(), change });
+                    tracker.patch(&rel, &on_disk, spec, &patch, &outcome);
+
+                    let keys = origins.entry(rel.clone()).or_default();
+                    for k in outcome.removed {
+                        if let Some(prev) = keys.shift_remove(&k) {
+                            conflicts.record(rel.clone(), Some(k), ConflictAction::Removed, prev, source.clone());
+                        }
+                    }
+                    for k in outcome.replaced {
+                        if let Some(prev) = keys.insert(k.clone(), source.clone()) {
+                            conflicts.record(rel.clone(), Some(k), ConflictAction::Replaced, prev, source.clone());
+                        }
+                    }
+                    for k in outcome.added {
+                        keys.insert(k, source.clone());
+                    }
+                    continue;
+                }
+            }
+
+            // Whole-file override: replace previous entry with the same relative path
+            record_entries(&mut origins, &rel, &s, &source);
+            tracker.whole(&rel, &on_disk, &s);
+            match existing {
+                Some(pos) => {
+                    // Everyone who contributed to the current version of the file loses
+                    let mut previous: Vec<ContentSource> = Vec::new();
+                    for o in file_history.iter().filter(|o| o.path == rel) {
+                        if o.change != FileChange::Patched {
+                            previous.clear();
+                        }
+                        if !previous.contains(&o.source) {
+                            previous.push(o.source.clone());
                         }
                     }
+                    for prev in previous.into_iter().filter(|p| p != &source) {
+                        conflicts.record(rel.clone(), None, ConflictAction::Replaced, prev, source.clone());
+                    }
+                    file_history.push(FileOrigin { path: rel.clone(), source: source.clone(), change: FileChange::Replaced });
+                    files[pos] = (rel, s);
+                }
+                None => {
+                    file_history.push(FileOrigin { path: rel.clone(), source: source.clone(), change: FileChange::Added });
+                    files.push((rel, s));
+                }
+            }
+        }
+    }
+
+    let entries = origins
+        .into_iter()
+        .flat_map(|(path, keys)| {
+            keys.into_iter().map(move |(key, source)| EntryOrigin { path: path.clone(), key, source })
+        })
+        .collect();
+    let source_map = tracker.finish(&files, &sources);
+    Ok(LoadedContent { mods, files, sources, source_map, file_history, entries, conflicts })
+}
+
+/// Which on-disk file each loaded file and list entry came from, while loading.
+#[derive(Default)]
+struct SourceTracker {
+    base: HashMap<PathBuf, PathBuf>,
+    /// Per loaded file: entry key -> (on-disk file, YAML path there)
+    entries: HashMap<PathBuf, HashMap<String, (PathBuf, String)>>,
+    patched: HashSet<PathBuf>,
+}
+
+impl SourceTracker {
+    fn whole(&mut self, rel: &Path, on_disk: &Path, contents: &str) {
+        self.base.insert(rel.to_path_buf(), on_disk.to_path_buf());
+        self.patched.remove(rel);
+        let keyed = mergeable_list_for(rel)
+            .and_then(|spec| Some(keyed_entries(spec, &serde_yaml::from_str(contents).ok()?, &[spec.list])))
+            .unwrap_or_default();
+        let entries = keyed.into_iter().map(|(key, at)| (key, (on_disk.to_path_buf(), at))).collect();
+        self.entries.insert(rel.to_path_buf(), entries);
+    }
+
+    fn patch(&mut self, rel: &Path, on_disk: &Path, spec: &MergeableList, patch: &serde_yaml::Value, outcome: &PatchOutcome) {
+        self.base.entry(rel.to_path_buf()).or_insert_with(|| on_disk.to_path_buf());
+        self.patched.insert(rel.to_path_buf());
+        let entries = self.entries.entry(rel.to_path_buf()).or_default();
+        for key in &outcome.removed {
+            entries.remove(key);
+        }
+        for op in ["replace", "add"] {
+            for (key, at) in keyed_entries(spec, patch, &[spec.list, op]) {
+                entries.insert(key, (on_disk.to_path_buf(), at));
+            }
+        }
+    }
+
+    fn finish(self, files: &[(PathBuf, String)], sources: &[(PathBuf, String)]) -> SourceMap {
+        let mut map = SourceMap::default();
+        for (rel, text) in files {
+            let Some(base) = self.base.get(rel) else { continue };
+            match mergeable_list_for(rel).filter(|_| self.patched.contains(rel)) {
+                Some(spec) => {
+                    let doc = serde_yaml::from_str(text).unwrap_or_default();
+                    let known = self.entries.get(rel);
+                    let entries = keyed_entries(spec, &doc, &[spec.list])
+                        .into_iter()
+                        .filter_map(|(key, at)| Some((at, known?.get(&key)?.clone())))
+                        .collect();
+                    map.merged(rel, base, text, entries, sources);
+                }
+                None => map.whole(rel, base),
+            }
+        }
+        map
+    }
+}
+
+/// Loads all YAML files from `game/` then overlays `/mods/*` (see [`load_content`]).
+/// Returns vector of (relative_path, contents)
+pub fn load_game_and_mods(root: &Path) -> Result<Vec<(PathBuf, String)>> {
+    Ok(load_content(root)?.files)
+}
+
+/// Reads every YAML/JSON file under `dir`, keyed by its path relative to `base`.
+fn read_content_files(dir: &Path, base: &Path) -> Result<Vec<(PathBuf, String)>> {
+    let mut out = Vec::new();
+    if !dir.exists() {
+        return Ok(out);
+    }
+    for entry in WalkDir::new(dir).sort_by_file_name().into_iter().filter_map(|e| e.ok()) {
+        if entry.file_type().is_file() {
+            if let Some(ext) = entry.path().extension() {
+                if ext == "yaml" || ext == "yml" || ext == "json" {
+                    let rel = entry.path().strip_prefix(base).unwrap_or(entry.path()).to_path_buf();
+                    let s = std::fs::read_to_string(entry.path())
+                        .with_context(|| format!("reading {}", entry.path().display()))?;
+                    out.push((rel, s));
                 }
             }
         }
     }
+    Ok(out)
+}
 
-    Ok(files)
+/// Attribute every entry of a (whole) mergeable list file to `source`.
+fn record_entries(
+    origins: &mut IndexMap<PathBuf, IndexMap<String, ContentSource>>,
+    rel: &Path,
+    contents: &str,
+    source: &ContentSource,
+) {
+    let Some(spec) = mergeable_list_for(rel) else { return };
+    let keys = serde_yaml::from_str::<serde_yaml::Value>(contents)
+        .map(|doc| list_keys(spec, &doc))
+        .unwrap_or_default();
+    origins.insert(rel.to_path_buf(), keys.into_iter().map(|k| (k, source.clone())).collect());
 }
 
 
@@ -89,4 +357,71 @@ mod tests {
         assert!(contents.contains("mod"));
         Ok(())
     }
+
+    #[test]
+    fn mods_apply_in_manifest_order_and_manifest_is_not_content() -> Result<(), anyhow::Error> {
+        let dir = tempdir()?;
+        let root = dir.path();
+        fs::create_dir_all(root.join("game"))?;
+        fs::write(root.join("game/foo.yaml"), "value: game")?;
+
+        // "a_patch" sorts first alphabetically but depends on "z_base", so it must win
+        for (id, deps) in [("a_patch", "[z_base]"), ("z_base", "[]")] {
+            let mod_root = root.join("mods").join(id);
+            fs::create_dir_all(mod_root.join("game"))?;
+            fs::write(mod_root.join("mod.yaml"), format!("id: {id}\nname: {id}\nversion: 1.0.0\ndepends_on: {deps}\n"))?;
+            fs::write(mod_root.join("game/foo.yaml"), format!("value: {id}"))?;
+        }
+
+        let order: Vec<String> = discover_mods(root)?.into_iter().map(|m| m.id).collect();
+        assert_eq!(order, vec!["z_base", "a_patch"]);
+
+        let content = load_content(root)?;
+        assert_eq!(content.files.len(), 1);
+        assert!(content.files[0].1.contains("a_patch"));
+
+        let conflict = &content.conflicts.conflicts[0];
+        assert_eq!(conflict.winner, ContentSource::Mod("a_patch".into()));
+        assert_eq!(conflict.losers, vec![ContentSource::Game, ContentSource::Mod("z_base".into())]);
+        Ok(())
+    }
+
+    #[test]
+    fn mod_patches_merge_entries_and_record_contributors() -> Result<(), anyhow::Error> {
+        let dir = tempdir()?;
+        let root = dir.path();
+        fs::create_dir_all(root.join("game/map"))?;
+        fs::write(
+            root.join("game/map/provinces.yaml"),
+            "provinces:\n  - { id: 0, name: A, pos: { x: 0, y: 0 } }\n  - { id: 1, name: B, pos: { x: 1, y: 0 } }\n",
+        )?;
+        let patches = [
+            ("east", "provinces:\n  add: [{ id: 2, name: C, pos: { x: 2, y: 0 } }]\n"),
+            ("west", "provinces:\n  add: [{ id: 3, name: D, pos: { x: 3, y: 0 } }]\n  replace: [{ id: 0, name: A2, pos: { x: 0, y: 0 } }]\n  remove: [1]\n"),
+        ];
+        for (id, patch) in patches {
+            let map_dir = root.join("mods").join(id).join("game/map");
+            fs::create_dir_all(&map_dir)?;
+            fs::write(map_dir.join("provinces.yaml"), patch)?;
+        }
+
+        let content = load_content(root)?;
+        let path = Path::new("game/map/provinces.yaml");
+        let list: crate::core::province::ProvincesList = serde_yaml::from_str(&content.files[0].1)?;
+        let ids: Vec<u32> = list.provinces.iter().map(|p| p.id).collect();
+        assert_eq!(ids, vec![0, 2, 3]);
+        assert_eq!(content.entry_source(path, "0"), Some(&ContentSource::Mod("west".into())));
+        assert_eq!(content.entry_source(path, "2"), Some(&ContentSource::Mod("east".into())));
+        assert_eq!(content.entry_source(path, "1"), None);
+
+        // "west" replaced the game's province 0 and removed province 1
+        let keyed: Vec<_> = content.conflicts.conflicts.iter().map(|c| (c.key.clone(), c.action, c.winner.clone())).collect();
+        assert_eq!(keyed, vec![
+            (Some("1".to_string()), ConflictAction::Removed, ContentSource::Mod("west".into())),
+            (Some("0".to_string()), ConflictAction::Replaced, ContentSource::Mod("west".into())),
+        ]);
+        let history: Vec<_> = content.file_sources(path).map(|o| o.change).collect();
+        assert_eq!(history, vec![FileChange::Added, FileChange::Patched, FileChange::Patched]);
+        Ok(())
+    }
 }
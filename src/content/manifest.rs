@@ -0,0 +1,239 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Version of the base game that mod manifests are checked against.
+pub const GAME_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Name of the manifest file expected at the root of every mod directory.
+pub const MANIFEST_FILE: &str = "mod.yaml";
+
+/// Contents of `mods/<dir>/mod.yaml`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModManifest {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    /// Mods that must be installed and are always loaded before this one.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Soft ordering: if these mods are installed they load before this one.
+    #[serde(default)]
+    pub load_after: Vec<String>,
+    #[serde(default)]
+    pub incompatible_with: Vec<String>,
+    /// Required game version, e.g. `0.1`, `0.1.0` or `>=0.1.0`.
+    #[serde(default)]
+    pub game_version: Option<String>,
+    /// Directory the mod was discovered in (not part of the manifest file).
+    #[serde(skip)]
+    pub dir: PathBuf,
+}
+
+impl ModManifest {
+    /// Manifest used for legacy mod directories without a `mod.yaml`.
+    pub fn implicit(dir: &Path) -> Self {
+        let id = dir.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        ModManifest {
+            name: id.clone(),
+            id,
+            version: "0.0.0".to_string(),
+            depends_on: Vec::new(),
+            load_after: Vec::new(),
+            incompatible_with: Vec::new(),
+            game_version: None,
+            dir: dir.to_path_buf(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ModError {
+    #[error("mod '{mod_id}' depends on '{missing}', which is not installed")]
+    MissingDependency { mod_id: String, missing: String },
+    #[error("mod '{a}' is incompatible with installed mod '{b}'")]
+    Incompatible { a: String, b: String },
+    #[error("dependency cycle between mods: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+    #[error("mod '{mod_id}' requires game version {required}, but this is {actual}")]
+    GameVersion { mod_id: String, required: String, actual: String },
+    #[error("mod id '{id}' is declared by both {} and {}", .first.display(), .second.display())]
+    DuplicateId { id: String, first: PathBuf, second: PathBuf },
+}
+
+/// All problems found while resolving the mod list.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("mod resolution failed:\n{}", .0.iter().map(|e| format!("- {}", e)).collect::<Vec<_>>().join("\n"))]
+pub struct ModErrors(pub Vec<ModError>);
+
+/// Check a manifest's `game_version` requirement against `actual`.
+/// Supports exact or prefix requirements (`0.1` matches `0.1.3`) and `>=X.Y.Z`.
+pub fn version_satisfies(required: &str, actual: &str) -> bool {
+    fn parts(v: &str) -> Vec<u64> {
+        v.trim().split('.').map(|p| p.parse().unwrap_or(0)).collect()
+    }
+    let required = required.trim();
+    if required.is_empty() || required == "*" {
+        return true;
+    }
+    if let Some(min) = required.strip_prefix(">=") {
+        let (mut min, mut act) = (parts(min), parts(actual));
+        let n = min.len().max(act.len());
+        min.resize(n, 0);
+        act.resize(n, 0);
+        return act >= min;
+    }
+    let req = parts(required);
+    let act = parts(actual);
+    req.len() <= act.len() && req.iter().zip(act.iter()).all(|(r, a)| r == a)
+}
+
+/// Validate the installed mods and return them in load order.
+///
+/// Dependencies and installed `load_after` targets load first; ties are broken
+/// alphabetically by id so the order is stable across machines.
+pub fn resolve_load_order(mods: Vec<ModManifest>) -> Result<Vec<ModManifest>, ModErrors> {
+    let mut errors = Vec::new();
+
+    let mut by_id: BTreeMap<String, ModManifest> = BTreeMap::new();
+    for m in mods {
+        if let Some(prev) = by_id.get(&m.id) {
+            errors.push(ModError::DuplicateId { id: m.id.clone(), first: prev.dir.clone(), second: m.dir.clone() });
+            continue;
+        }
+        by_id.insert(m.id.clone(), m);
+    }
+
+    for m in by_id.values() {
+        if let Some(req) = &m.game_version {
+            if !version_satisfies(req, GAME_VERSION) {
+                errors.push(ModError::GameVersion {
+                    mod_id: m.id.clone(),
+                    required: req.clone(),
+                    actual: GAME_VERSION.to_string(),
+                });
+            }
+        }
+        for dep in &m.depends_on {
+            if !by_id.contains_key(dep) {
+                errors.push(ModError::MissingDependency { mod_id: m.id.clone(), missing: dep.clone() });
+            }
+        }
+        for other in &m.incompatible_with {
+            // Report each pair once, even if both sides declare it
+            let declared_by_other = by_id.get(other).is_some_and(|o| o.incompatible_with.contains(&m.id));
+            if by_id.contains_key(other) && !(declared_by_other && other < &m.id) {
+                errors.push(ModError::Incompatible { a: m.id.clone(), b: other.clone() });
+            }
+        }
+    }
+
+    // Kahn's algorithm over "must load before" edges
+    let mut incoming: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+    for m in by_id.values() {
+        let before = incoming.entry(m.id.as_str()).or_default();
+        for dep in m.depends_on.iter().chain(m.load_after.iter()) {
+            if by_id.contains_key(dep) {
+                before.insert(dep.as_str());
+            }
+        }
+    }
+    let mut ready: BTreeSet<&str> = incoming.iter().filter(|(_, b)| b.is_empty()).map(|(id, _)| *id).collect();
+    let mut order: Vec<&str> = Vec::new();
+    while let Some(id) = ready.iter().next().copied() {
+        ready.remove(id);
+        order.push(id);
+        incoming.remove(id);
+        for (other, before) in incoming.iter_mut() {
+            if before.remove(id) && before.is_empty() {
+                ready.insert(*other);
+            }
+        }
+    }
+    if !incoming.is_empty() {
+        errors.push(ModError::Cycle(find_cycle(&incoming)));
+    }
+
+    if !errors.is_empty() {
+        return Err(ModErrors(errors));
+    }
+    let order: Vec<String> = order.into_iter().map(str::to_string).collect();
+    Ok(order.into_iter().filter_map(|id| by_id.remove(&id)).collect())
+}
+
+/// Follow unresolved ordering edges until a mod repeats, returning the loop
+/// as `a -> b -> a` where each mod must load after the next one.
+fn find_cycle(incoming: &HashMap<&str, BTreeSet<&str>>) -> Vec<String> {
+    let Some(start) = incoming.keys().min().copied() else { return Vec::new() };
+    let mut path: Vec<&str> = vec![start];
+    let mut cur = start;
+    while let Some(next) = incoming.get(cur).and_then(|b| b.iter().find(|n| incoming.contains_key(*n))).copied() {
+        if let Some(pos) = path.iter().position(|p| *p == next) {
+            let mut cycle: Vec<String> = path[pos..].iter().map(|s| s.to_string()).collect();
+            cycle.push(next.to_string());
+            return cycle;
+        }
+        path.push(next);
+        cur = next;
+    }
+    path.into_iter().map(str::to_string).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(id: &str, depends_on: &[&str], load_after: &[&str]) -> ModManifest {
+        let mut m = ModManifest::implicit(Path::new(id));
+        m.depends_on = depends_on.iter().map(|s| s.to_string()).collect();
+        m.load_after = load_after.iter().map(|s| s.to_string()).collect();
+        m
+    }
+
+    fn ids(mods: &[ModManifest]) -> Vec<&str> {
+        mods.iter().map(|m| m.id.as_str()).collect()
+    }
+
+    #[test]
+    fn load_order_respects_dependencies_then_alphabetical() {
+        let mods = vec![
+            manifest("a_ui", &["z_core"], &[]),
+            manifest("m_map", &[], &["a_ui", "not_installed"]),
+            manifest("z_core", &[], &[]),
+            manifest("b_extra", &[], &[]),
+        ];
+        let order = resolve_load_order(mods).unwrap();
+        assert_eq!(ids(&order), vec!["b_extra", "z_core", "a_ui", "m_map"]);
+    }
+
+    #[test]
+    fn missing_dependency_and_cycle_are_reported() {
+        let mods = vec![
+            manifest("a", &["b"], &[]),
+            manifest("b", &[], &["a"]),
+            manifest("c", &["ghost"], &[]),
+        ];
+        let errs = resolve_load_order(mods).unwrap_err().0;
+        assert!(errs.contains(&ModError::MissingDependency { mod_id: "c".into(), missing: "ghost".into() }));
+        assert!(errs.iter().any(|e| matches!(e, ModError::Cycle(c) if c.contains(&"a".to_string()) && c.contains(&"b".to_string()))));
+    }
+
+    #[test]
+    fn incompatible_pair_reported_once() {
+        let mut a = manifest("a", &[], &[]);
+        a.incompatible_with = vec!["b".into()];
+        let mut b = manifest("b", &[], &[]);
+        b.incompatible_with = vec!["a".into()];
+        let errs = resolve_load_order(vec![a, b]).unwrap_err().0;
+        assert_eq!(errs, vec![ModError::Incompatible { a: "a".into(), b: "b".into() }]);
+    }
+
+    #[test]
+    fn game_version_requirements() {
+        assert!(version_satisfies("0.1", "0.1.0"));
+        assert!(version_satisfies(">=0.0.9", "0.1.0"));
+        assert!(!version_satisfies("0.2", "0.1.0"));
+        assert!(!version_satisfies(">=1.0", "0.1.0"));
+    }
+}
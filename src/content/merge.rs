@@ -0,0 +1,230 @@
+use serde_yaml::{Mapping, Value};
+use std::path::Path;
+use thiserror::Error;
+
+/// How entries of a mergeable list are identified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyKind {
+    /// Entries carry an `id` field (provinces, focuses, battalions, technologies).
+    Id,
+    /// Undirected adjacency edges keyed by their `a`/`b` endpoints.
+    Edge,
+}
+
+/// A content file whose top-level list can be patched entry by entry.
+#[derive(Debug, Clone, Copy)]
+pub struct MergeableList {
+    pub file_name: &'static str,
+    pub list: &'static str,
+    pub key: KeyKind,
+}
+
+pub const MERGEABLE_LISTS: &[MergeableList] = &[
+    MergeableList { file_name: "provinces.yaml", list: "provinces", key: KeyKind::Id },
+    MergeableList { file_name: "focus_tree.yaml", list: "focuses", key: KeyKind::Id },
+    MergeableList { file_name: "battalions.yaml", list: "battalions", key: KeyKind::Id },
+    MergeableList { file_name: "technologies.yaml", list: "technologies", key: KeyKind::Id },
+    MergeableList { file_name: "adjacency.yaml", list: "edges", key: KeyKind::Edge },
+];
+
+const PATCH_OPS: [&str; 3] = ["add", "replace", "remove"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum MergeError {
+    #[error("'{list}' entry {key} is added but already exists; use `replace` instead")]
+    AlreadyExists { list: String, key: String },
+    #[error("'{list}' entry {key} cannot be replaced because it does not exist")]
+    MissingReplace { list: String, key: String },
+    #[error("'{list}' entry {key} cannot be removed because it does not exist")]
+    MissingRemove { list: String, key: String },
+    #[error("'{list}' entry has no usable key: {entry}")]
+    NoKey { list: String, entry: String },
+    #[error("'{list}.{op}' must be a list")]
+    NotAList { list: String, op: String },
+    #[error("base file has no '{list}' list to patch")]
+    MissingBaseList { list: String },
+}
+
+/// Keys touched by a patch, in the order they were applied.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PatchOutcome {
+    pub added: Vec<String>,
+    pub replaced: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// Look up the merge rules for a content file by its file name.
+pub fn mergeable_list_for(path: &Path) -> Option<&'static MergeableList> {
+    MERGEABLE_LISTS.iter().find(|m| path.ends_with(m.file_name))
+}
+
+/// Stable key for a list entry: the `id` rendered as text, or `a-b` (lowest
+/// endpoint first) for edges so `{a: 1, b: 0}` and `{a: 0, b: 1}` match.
+pub fn entry_key(kind: KeyKind, entry: &Value) -> Option<String> {
+    match kind {
+        KeyKind::Id => scalar_key(entry.get("id")?),
+        KeyKind::Edge => {
+            let a = entry.get("a")?.as_u64()?;
+            let b = entry.get("b")?.as_u64()?;
+            Some(format!("{}-{}", a.min(b), a.max(b)))
+        }
+    }
+}
+
+fn scalar_key(v: &Value) -> Option<String> {
+    match v {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// A document is a patch when its list key holds a mapping of `add`/`replace`/`remove`
+/// instead of a plain sequence, e.g. `provinces: { add: [...], remove: [3] }`.
+pub fn is_patch(spec: &MergeableList, doc: &Value) -> bool {
+    match doc.get(spec.list) {
+        Some(Value::Mapping(m)) => PATCH_OPS.iter().any(|op| m.contains_key(*op)),
+        _ => false,
+    }
+}
+
+/// Keys of every entry in the document's list, in file order.
+pub fn list_keys(spec: &MergeableList, doc: &Value) -> Vec<String> {
+    doc.get(spec.list)
+        .and_then(|l| l.as_sequence())
+        .map(|seq| seq.iter().filter_map(|e| entry_key(spec.key, e)).collect())
+        .unwrap_or_default()
+}
+
+/// Keys of the entries in the list at `at`, e.g. `["provinces", "add"]`,
+/// each with its YAML path such as `provinces.add.0`.
+pub fn keyed_entries(spec: &MergeableList, doc: &Value, at: &[&str]) -> Vec<(String, String)> {
+    let list = at.iter().try_fold(doc, |node, seg| node.get(*seg)).and_then(|l| l.as_sequence());
+    list.map(|seq| {
+        seq.iter()
+            .enumerate()
+            .filter_map(|(i, e)| Some((entry_key(spec.key, e)?, format!("{}.{}", at.join("."), i))))
+            .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// Apply a patch document onto `base` in place. Removals run first, then
+/// replacements (which keep their position), then additions (appended).
+/// Any other top-level keys in the patch overwrite the base value.
+pub fn apply_patch(spec: &MergeableList, base: &mut Value, patch: &Value) -> Result<PatchOutcome, MergeError> {
+    let list = spec.list.to_string();
+    let ops = patch.get(spec.list).and_then(|v| v.as_mapping()).cloned().unwrap_or_default();
+    let op_entries = |op: &str| -> Result<Vec<Value>, MergeError> {
+        match ops.get(op) {
+            None | Some(Value::Null) => Ok(Vec::new()),
+            Some(Value::Sequence(s)) => Ok(s.clone()),
+            Some(_) => Err(MergeError::NotAList { list: list.clone(), op: op.to_string() }),
+        }
+    };
+    let key_of = |entry: &Value| -> Result<String, MergeError> {
+        entry_key(spec.key, entry).ok_or_else(|| MergeError::NoKey { list: list.clone(), entry: render(entry) })
+    };
+
+    let (removes, replaces, adds) = (op_entries("remove")?, op_entries("replace")?, op_entries("add")?);
+    let Some(seq) = base.get_mut(spec.list).and_then(|v| v.as_sequence_mut()) else {
+        return Err(MergeError::MissingBaseList { list });
+    };
+    let mut outcome = PatchOutcome::default();
+
+    for r in &removes {
+        // `remove` accepts bare ids as well as full entries
+        let key = match (spec.key, scalar_key(r)) {
+            (KeyKind::Id, Some(k)) => k,
+            _ => key_of(r)?,
+        };
+        let pos = seq.iter().position(|e| entry_key(spec.key, e).as_deref() == Some(key.as_str()))
+            .ok_or_else(|| MergeError::MissingRemove { list: list.clone(), key: key.clone() })?;
+        seq.remove(pos);
+        outcome.removed.push(key);
+    }
+    for r in replaces {
+        let key = key_of(&r)?;
+        let slot = seq.iter_mut().find(|e| entry_key(spec.key, e).as_deref() == Some(key.as_str()))
+            .ok_or_else(|| MergeError::MissingReplace { list: list.clone(), key: key.clone() })?;
+        *slot = r;
+        outcome.replaced.push(key);
+    }
+    for a in adds {
+        let key = key_of(&a)?;
+        if seq.iter().any(|e| entry_key(spec.key, e).as_deref() == Some(key.as_str())) {
+            return Err(MergeError::AlreadyExists { list, key });
+        }
+        seq.push(a);
+        outcome.added.push(key);
+    }
+
+    if let (Some(base_map), Some(patch_map)) = (base.as_mapping_mut(), patch.as_mapping()) {
+        for (k, v) in patch_map {
+            if k.as_str() != Some(spec.list) {
+                base_map.insert(k.clone(), v.clone());
+            }
+        }
+    }
+    Ok(outcome)
+}
+
+fn render(v: &Value) -> String {
+    serde_yaml::to_string(v).map(|s| s.trim().replace('\n', ", ")).unwrap_or_default()
+}
+
+/// Empty document with the given list, used when a patch has nothing to apply to.
+pub fn empty_list_doc(spec: &MergeableList) -> Value {
+    let mut m = Mapping::new();
+    m.insert(Value::String(spec.list.to_string()), Value::Sequence(Vec::new()));
+    Value::Mapping(m)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provinces_spec() -> &'static MergeableList {
+        mergeable_list_for(Path::new("game/map/provinces.yaml")).unwrap()
+    }
+
+    #[test]
+    fn patch_adds_replaces_and_removes_by_id() {
+        let mut base: Value = serde_yaml::from_str(
+            "provinces:\n  - { id: 0, name: A }\n  - { id: 1, name: B }\n  - { id: 3, name: D }\n",
+        ).unwrap();
+        let patch: Value = serde_yaml::from_str(
+            "provinces:\n  add: [{ id: 4, name: E }]\n  replace: [{ id: 1, name: B2 }]\n  remove: [3]\n",
+        ).unwrap();
+        let spec = provinces_spec();
+        assert!(is_patch(spec, &patch));
+        assert!(!is_patch(spec, &base));
+
+        let out = apply_patch(spec, &mut base, &patch).unwrap();
+        assert_eq!(out, PatchOutcome { added: vec!["4".into()], replaced: vec!["1".into()], removed: vec!["3".into()] });
+        assert_eq!(list_keys(spec, &base), vec!["0", "1", "4"]);
+        assert_eq!(base["provinces"][1]["name"].as_str(), Some("B2"));
+    }
+
+    #[test]
+    fn edges_match_regardless_of_direction() {
+        let spec = mergeable_list_for(Path::new("game/map/adjacency.yaml")).unwrap();
+        let mut base: Value = serde_yaml::from_str("edges:\n  - { a: 0, b: 1 }\n  - { a: 1, b: 2 }\n").unwrap();
+        let patch: Value = serde_yaml::from_str("edges:\n  remove: [{ a: 1, b: 0 }]\n").unwrap();
+        apply_patch(spec, &mut base, &patch).unwrap();
+        assert_eq!(list_keys(spec, &base), vec!["1-2"]);
+    }
+
+    #[test]
+    fn invalid_operations_are_errors() {
+        let spec = provinces_spec();
+        let mut base: Value = serde_yaml::from_str("provinces:\n  - { id: 0, name: A }\n").unwrap();
+        let dup: Value = serde_yaml::from_str("provinces:\n  add: [{ id: 0, name: X }]\n").unwrap();
+        assert_eq!(
+            apply_patch(spec, &mut base, &dup),
+            Err(MergeError::AlreadyExists { list: "provinces".into(), key: "0".into() })
+        );
+        let missing: Value = serde_yaml::from_str("provinces:\n  remove: [9]\n").unwrap();
+        assert!(matches!(apply_patch(spec, &mut base, &missing), Err(MergeError::MissingRemove { .. })));
+    }
+}
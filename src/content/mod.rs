@@ -1,5 +1,33 @@
+pub mod config;
+pub mod conflicts;
+pub mod db;
+pub mod diagnostics;
+pub mod focus_analysis;
 pub mod loader;
+pub mod manifest;
+pub mod merge;
+pub mod pipeline;
+pub mod reload;
+pub mod schemas;
+pub mod sources;
+pub mod spans;
+pub mod suppress;
 pub mod validator;
+pub mod watch;
 
+pub use config::*;
+pub use conflicts::*;
+pub use db::*;
+pub use diagnostics::*;
+pub use focus_analysis::*;
 pub use loader::*;
+pub use manifest::*;
+pub use merge::*;
+pub use pipeline::*;
+pub use reload::*;
+pub use schemas::*;
+pub use sources::*;
+pub use spans::*;
+pub use suppress::*;
 pub use validator::*;
+pub use watch::*;
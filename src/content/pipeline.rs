@@ -0,0 +1,76 @@
+use std::fmt;
+use std::path::Path;
+use thiserror::Error;
+
+use crate::content::config::ValidateConfig;
+use crate::content::db::ContentDb;
+use crate::content::loader::load_content;
+use crate::content::validator::{schema_diagnostics, structural_diagnostics};
+
+/// Why content could not be used to start (or reload) the game.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub struct ContentReport {
+    pub errors: Vec<String>,
+}
+
+impl fmt::Display for ContentReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Game content failed validation with {} error(s):", self.errors.len())?;
+        for (i, e) in self.errors.iter().enumerate() {
+            // Keep multi-line messages (schema failures) indented under their bullet
+            writeln!(f, "[{}] {}", i + 1, e.trim_end().replace('\n', "\n    "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Run the same pipeline as the `validate` binary — load `game/` and mods,
+/// schema checks, typed parsing and structural checks — and return the
+/// database only if nothing failed. Rule levels and suppression comments
+/// from `validate.yaml` apply here too; warnings never block loading.
+pub fn load_validated(root: &Path) -> Result<ContentDb, ContentReport> {
+    let report = |e: anyhow::Error| ContentReport { errors: vec![format!("{:#}", e)] };
+    let config = ValidateConfig::load(root).map_err(report)?;
+    let content = load_content(root).map_err(report)?;
+
+    let mut diags: Vec<_> = schema_diagnostics(&root.join("schemas"), &content.files, false)
+        .into_iter()
+        .flat_map(|(_, d)| d)
+        .collect();
+
+    let db = ContentDb::from_files(&content.files);
+    diags.extend(structural_diagnostics(&db));
+    let diags = diags.into_iter().map(|d| content.source_map.relocate(d)).collect();
+    let (diags, _) = config.apply(diags, &content.sources);
+    let errors: Vec<String> = diags.into_iter().filter(|d| d.is_error()).map(|d| d.message).collect();
+
+    if errors.is_empty() {
+        Ok(db)
+    } else {
+        Err(ContentReport { errors })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn broken_mod_content_blocks_loading_with_report() -> Result<(), anyhow::Error> {
+        let dir = tempdir()?;
+        let root = dir.path();
+        fs::create_dir_all(root.join("game/map"))?;
+        fs::write(root.join("game/map/provinces.yaml"), "provinces:\n  - { id: 0, name: A, pos: { x: 0, y: 0 }, unowned: true }\n")?;
+        assert_eq!(load_validated(root)?.provinces.len(), 1);
+
+        let map = root.join("mods/bad/game/map");
+        fs::create_dir_all(&map)?;
+        fs::write(map.join("adjacency.yaml"), "edges:\n  - { a: 0, b: 7 }\n")?;
+        let report = load_validated(root).unwrap_err();
+        assert_eq!(report.errors, vec!["Adjacency edge references missing province b=7".to_string()]);
+        assert!(report.to_string().starts_with("Game content failed validation with 1 error(s):"));
+        Ok(())
+    }
+}
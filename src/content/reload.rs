@@ -0,0 +1,193 @@
+use serde::Serialize;
+use std::fmt;
+use std::path::Path;
+
+use crate::content::db::ContentDb;
+use crate::content::pipeline::{load_validated, ContentReport};
+use crate::core::units::BattalionStats;
+
+/// A content change that can be applied to a running game.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LiveChange {
+    FocusName { country: String, focus: String, name: String },
+    FocusDays { country: String, focus: String, old: u32, new: u32 },
+    ProvinceName { id: u32, name: String },
+    BattalionStats { id: String, stats: BattalionStats },
+}
+
+impl fmt::Display for LiveChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LiveChange::FocusName { country, focus, name } => write!(f, "focus {}/{} renamed to '{}'", country, focus, name),
+            LiveChange::FocusDays { country, focus, old, new } => write!(f, "focus {}/{} takes {} days (was {})", country, focus, new, old),
+            LiveChange::ProvinceName { id, name } => write!(f, "province {} renamed to '{}'", id, name),
+            LiveChange::BattalionStats { id, .. } => write!(f, "battalion {} stats updated", id),
+        }
+    }
+}
+
+/// Differences between the running content and freshly loaded content.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContentDiff {
+    /// Changes applied without a restart
+    pub live: Vec<LiveChange>,
+    /// Sections that changed in ways the running game cannot pick up
+    pub needs_restart: Vec<String>,
+}
+
+impl ContentDiff {
+    pub fn is_empty(&self) -> bool {
+        self.live.is_empty() && self.needs_restart.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReloadOutcome {
+    /// New content validated; `live` changes were applied to the current database.
+    Applied(ContentDiff),
+    /// New content failed validation; the current database was left untouched.
+    Rejected(ContentReport),
+}
+
+/// Reload and validate content under `root`. If it is valid, apply the safe
+/// subset of changes (focus names and days, province names, battalion stats)
+/// to `current`; everything else is reported as needing a restart.
+pub fn reload_into(root: &Path, current: &mut ContentDb) -> ReloadOutcome {
+    match load_validated(root) {
+        Ok(fresh) => {
+            let diff = diff_content(current, &fresh);
+            apply_live(current, &diff.live);
+            ReloadOutcome::Applied(diff)
+        }
+        Err(report) => ReloadOutcome::Rejected(report),
+    }
+}
+
+/// Compare two databases, splitting changes into live-applicable ones and
+/// sections that would need a restart.
+pub fn diff_content(old: &ContentDb, new: &ContentDb) -> ContentDiff {
+    let mut live = Vec::new();
+    for (tag, tree) in &new.focus_trees {
+        let Some(old_tree) = old.focus_tree(tag) else { continue };
+        for f in &tree.focuses {
+            let Some(prev) = old_tree.find(&f.id) else { continue };
+            if prev.name != f.name {
+                live.push(LiveChange::FocusName { country: tag.clone(), focus: f.id.clone(), name: f.name.clone() });
+            }
+            if prev.days != f.days {
+                live.push(LiveChange::FocusDays { country: tag.clone(), focus: f.id.clone(), old: prev.days, new: f.days });
+            }
+        }
+    }
+    for (id, p) in &new.provinces {
+        if old.province(*id).is_some_and(|prev| prev.name != p.name) {
+            live.push(LiveChange::ProvinceName { id: *id, name: p.name.clone() });
+        }
+    }
+    for (id, b) in &new.battalions {
+        if old.battalion(id).is_some_and(|prev| prev.stats != b.stats) {
+            live.push(LiveChange::BattalionStats { id: id.clone(), stats: b.stats.clone() });
+        }
+    }
+
+    // Whatever still differs once the live changes are applied needs a restart
+    let mut patched = old.clone();
+    apply_live(&mut patched, &live);
+    let mut needs_restart = Vec::new();
+    let sections = [
+        ("provinces", section(patched.provinces.values()) != section(new.provinces.values())),
+        ("adjacency", section(patched.edges.iter()) != section(new.edges.iter())),
+        ("countries", section(patched.countries.values()) != section(new.countries.values())),
+        ("focus trees", section(patched.focus_trees.iter()) != section(new.focus_trees.iter())),
+        ("battalions", section(patched.battalions.values()) != section(new.battalions.values())),
+        ("technologies", section(patched.techs.values()) != section(new.techs.values())),
+        ("research", section(patched.research.iter()) != section(new.research.iter())),
+        ("scenario", section(patched.scenario.iter()) != section(new.scenario.iter())),
+        ("speeds", section(patched.speeds.iter()) != section(new.speeds.iter())),
+        ("events", section(patched.events.values()) != section(new.events.values())),
+        ("ideas", section(patched.ideas.values()) != section(new.ideas.values())),
+        ("ideologies", section(patched.ideologies.values()) != section(new.ideologies.values())),
+        ("politics rules", section([&patched.politics].into_iter()) != section([&new.politics].into_iter())),
+        ("characters", section(patched.characters.values()) != section(new.characters.values())),
+        ("traits", section(patched.traits.values()) != section(new.traits.values())),
+    ];
+    for (name, changed) in sections {
+        if changed {
+            needs_restart.push(name.to_string());
+        }
+    }
+    ContentDiff { live, needs_restart }
+}
+
+/// Apply live changes to a database in place.
+pub fn apply_live(db: &mut ContentDb, changes: &[LiveChange]) {
+    for c in changes {
+        match c {
+            LiveChange::FocusName { country, focus, name } => {
+                if let Some(f) = db.focus_trees.get_mut(country).and_then(|t| t.focuses.iter_mut().find(|f| &f.id == focus)) {
+                    f.name = name.clone();
+                }
+            }
+            LiveChange::FocusDays { country, focus, new, .. } => {
+                if let Some(f) = db.focus_trees.get_mut(country).and_then(|t| t.focuses.iter_mut().find(|f| &f.id == focus)) {
+                    f.days = *new;
+                }
+            }
+            LiveChange::ProvinceName { id, name } => {
+                if let Some(p) = db.provinces.get_mut(id) {
+                    p.name = name.clone();
+                }
+            }
+            LiveChange::BattalionStats { id, stats } => {
+                if let Some(b) = db.battalions.get_mut(id) {
+                    b.stats = stats.clone();
+                }
+            }
+        }
+    }
+}
+
+fn section<T: Serialize>(items: impl Iterator<Item = T>) -> serde_json::Value {
+    serde_json::to_value(items.collect::<Vec<_>>()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    const PROVINCES: &str = "provinces:\n  - { id: 0, name: Aachen, pos: { x: 0, y: 0 }, unowned: true }\n";
+    const FOCUSES: &str = "focuses:\n  - { id: a, name: Alpha, days: 10 }\n";
+
+    #[test]
+    fn reload_applies_safe_changes_and_keeps_last_good_content() -> Result<(), anyhow::Error> {
+        let dir = tempdir()?;
+        let root = dir.path();
+        fs::create_dir_all(root.join("game/map"))?;
+        fs::create_dir_all(root.join("game/countries/GER"))?;
+        fs::write(root.join("game/map/provinces.yaml"), PROVINCES)?;
+        fs::write(root.join("game/countries/GER/focus_tree.yaml"), FOCUSES)?;
+        let mut db = load_validated(root).unwrap();
+
+        // Rename a province, shorten a focus and move the province: the move needs a restart
+        fs::write(root.join("game/map/provinces.yaml"), "provinces:\n  - { id: 0, name: Aix, pos: { x: 5, y: 0 }, unowned: true }\n")?;
+        fs::write(root.join("game/countries/GER/focus_tree.yaml"), "focuses:\n  - { id: a, name: Alpha, days: 7 }\n")?;
+        let ReloadOutcome::Applied(diff) = reload_into(root, &mut db) else { panic!("reload rejected") };
+        assert_eq!(diff.live, vec![
+            LiveChange::FocusDays { country: "GER".into(), focus: "a".into(), old: 10, new: 7 },
+            LiveChange::ProvinceName { id: 0, name: "Aix".into() },
+        ]);
+        assert_eq!(diff.needs_restart, vec!["provinces".to_string()]);
+        assert_eq!(db.province(0).unwrap().name, "Aix");
+        assert_eq!(db.province(0).unwrap().pos.x, 0);
+        assert_eq!(db.focus_tree("GER").unwrap().find("a").unwrap().days, 7);
+
+        // Broken content is rejected and the running database is untouched
+        fs::write(root.join("game/countries/GER/focus_tree.yaml"), "focuses:\n  - { id: a, name: Alpha, days: 3, prerequisites: [ghost] }\n")?;
+        let ReloadOutcome::Rejected(report) = reload_into(root, &mut db) else { panic!("broken content accepted") };
+        assert!(report.errors[0].contains("unknown prerequisite ghost"));
+        assert_eq!(db.focus_tree("GER").unwrap().find("a").unwrap().days, 7);
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+use anyhow::{Context, Result};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use schemars::{gen::SchemaSettings, JsonSchema};
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+use crate::core::{
+    adjacency::Adjacency,
+    characters::{CharacterFile, TraitFile},
+    country::CountryDef,
+    events::EventFile,
+    focus::FocusTree,
+    ideas::IdeaFile,
+    politics::IdeologyFile,
+    province::ProvincesList,
+    research::{CountryResearch, Technologies},
+    scenario::ScenarioDef,
+    time::SpeedTable,
+    units::Battalions,
+};
+
+/// Registry file inside the schemas directory.
+pub const REGISTRY_FILE: &str = "registry.yaml";
+
+/// One registry entry: content files matching `pattern` are checked against `schema`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SchemaRule {
+    pub pattern: String,
+    /// File name inside the schemas directory
+    pub schema: String,
+}
+
+#[derive(Deserialize)]
+struct RegistryFile {
+    schemas: Vec<SchemaRule>,
+}
+
+/// Maps content paths (relative to the content root) to JSON schemas using
+/// glob patterns from `schemas/registry.yaml`. `*` does not cross `/`; use
+/// `**` for any depth. The first matching rule wins.
+#[derive(Debug, Clone)]
+pub struct SchemaRegistry {
+    dir: PathBuf,
+    rules: Vec<SchemaRule>,
+    globs: GlobSet,
+}
+
+impl SchemaRegistry {
+    pub fn load(schemas_dir: &Path) -> Result<Self> {
+        let path = schemas_dir.join(REGISTRY_FILE);
+        let text = std::fs::read_to_string(&path).with_context(|| format!("reading schema registry {}", path.display()))?;
+        let file: RegistryFile =
+            serde_yaml::from_str(&text).with_context(|| format!("parsing schema registry {}", path.display()))?;
+        Self::from_rules(schemas_dir, file.schemas)
+    }
+
+    pub fn from_rules(schemas_dir: &Path, rules: Vec<SchemaRule>) -> Result<Self> {
+        let mut builder = GlobSetBuilder::new();
+        for rule in &rules {
+            let glob = GlobBuilder::new(&rule.pattern)
+                .literal_separator(true)
+                .build()
+                .with_context(|| format!("invalid schema pattern {:?}", rule.pattern))?;
+            builder.add(glob);
+        }
+        let globs = builder.build().context("building schema patterns")?;
+        Ok(SchemaRegistry { dir: schemas_dir.to_path_buf(), rules, globs })
+    }
+
+    pub fn rules(&self) -> &[SchemaRule] {
+        &self.rules
+    }
+
+    /// Schema file covering the content file at `path`, if any rule matches.
+    pub fn schema_for(&self, path: &Path) -> Option<PathBuf> {
+        // Patterns are written with `/` whatever the platform
+        let normalized = path.to_string_lossy().replace('\\', "/");
+        let first = self.globs.matches(normalized.as_str()).into_iter().min()?;
+        Some(self.dir.join(&self.rules[first].schema))
+    }
+}
+
+/// Every schema in `schemas/`, generated from the Rust type each content
+/// file deserializes into. Regenerate with `validate --emit-schemas`.
+///
+/// None of them reject unknown fields: `--strict` reports those, honouring
+/// the extension fields `validate.yaml` allows per content type.
+pub fn generated_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("adjacency.schema.json", schema_for::<Adjacency>()),
+        ("battalions.schema.json", schema_for::<Battalions>()),
+        ("country.schema.json", schema_for::<CountryDef>()),
+        ("focus_tree.schema.json", schema_for::<FocusTree>()),
+        ("provinces.schema.json", schema_for::<ProvincesList>()),
+        ("research.schema.json", schema_for::<CountryResearch>()),
+        ("scenario.schema.json", schema_for::<ScenarioDef>()),
+        ("speeds.schema.json", schema_for::<SpeedTable>()),
+        ("events.schema.json", schema_for::<EventFile>()),
+        ("ideas.schema.json", schema_for::<IdeaFile>()),
+        ("ideologies.schema.json", schema_for::<IdeologyFile>()),
+        ("characters.schema.json", schema_for::<CharacterFile>()),
+        ("traits.schema.json", schema_for::<TraitFile>()),
+        ("technologies.schema.json", schema_for::<Technologies>()),
+    ]
+}
+
+/// Draft 7, to match what the validator compiles schemas with.
+fn schema_for<T: JsonSchema>() -> Value {
+    let schema = SchemaSettings::draft07().into_generator().into_root_schema_for::<T>();
+    serde_json::to_value(schema).expect("schemas always serialize")
+}
+
+/// File contents for a generated schema, as written by [`emit_schemas`].
+pub fn schema_text(schema: &Value) -> String {
+    let mut text = serde_json::to_string_pretty(schema).expect("schemas always serialize");
+    text.push('\n');
+    text
+}
+
+/// Write every generated schema into `dir`, returning the paths written.
+pub fn emit_schemas(dir: &Path) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    let mut written = Vec::new();
+    for (name, schema) in generated_schemas() {
+        let path = dir.join(name);
+        std::fs::write(&path, schema_text(&schema)).with_context(|| format!("writing {}", path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Names of generated schemas whose file in `dir` is missing or different.
+pub fn stale_schemas(dir: &Path) -> Vec<&'static str> {
+    generated_schemas()
+        .into_iter()
+        .filter(|(name, schema)| std::fs::read_to_string(dir.join(name)).ok() != Some(schema_text(schema)))
+        .map(|(name, _)| name)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patterns_pick_schemas_by_path_not_file_name() -> Result<()> {
+        let rule = |pattern: &str, schema: &str| SchemaRule { pattern: pattern.into(), schema: schema.into() };
+        let registry = SchemaRegistry::from_rules(Path::new("schemas"), vec![
+            rule("game/map/provinces.yaml", "provinces.schema.json"),
+            rule("game/countries/*/country.yaml", "country.schema.json"),
+            rule("game/**/*.yaml", "fallback.schema.json"),
+        ])?;
+        let schema = |p: &str| registry.schema_for(Path::new(p));
+
+        assert_eq!(schema("game/map/provinces.yaml"), Some(PathBuf::from("schemas/provinces.schema.json")));
+        assert_eq!(schema("game/countries/GER/country.yaml"), Some(PathBuf::from("schemas/country.schema.json")));
+        // Same file name elsewhere only gets the catch-all
+        assert_eq!(schema("game/history/provinces.yaml"), Some(PathBuf::from("schemas/fallback.schema.json")));
+        assert_eq!(schema("game/countries/GER/extra/country.yaml"), Some(PathBuf::from("schemas/fallback.schema.json")));
+        assert_eq!(schema("common/provinces.yaml"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn checked_in_schemas_match_rust_types() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("schemas");
+        let stale = stale_schemas(&dir);
+        assert!(stale.is_empty(), "schemas out of date: {:?}; run `cargo run --bin validate -- --emit-schemas`", stale);
+
+        // Every registered schema is one we generate
+        let generated: Vec<&str> = generated_schemas().into_iter().map(|(name, _)| name).collect();
+        let registry = SchemaRegistry::load(&dir).unwrap();
+        for rule in registry.rules() {
+            assert!(generated.contains(&rule.schema.as_str()), "{} is not generated", rule.schema);
+        }
+
+        // Extension fields are left to `--strict` and its allow list
+        let country = serde_json::json!({ "tag": "GER", "name": "Germany", "x_author": "me" });
+        for (_, schema) in generated_schemas() {
+            assert_eq!(schema.get("additionalProperties"), None);
+        }
+        let schema = generated_schemas().into_iter().find(|(name, _)| *name == "country.schema.json").unwrap().1;
+        assert!(crate::content::validator::validate_value(schema, &country).is_ok());
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::content::diagnostics::{Diagnostic, Location};
+use crate::content::spans::{Span, SpanIndex};
+
+/// Where the text of each loaded content file came from on disk, so
+/// diagnostics found in merged content point at the files modders edit.
+///
+/// Files are loaded under virtual paths like `game/map/provinces.yaml`.
+/// A file that one source defined whole maps line for line to that source,
+/// e.g. `mods/a/game/map/provinces.yaml`. A file that mod patches were merged
+/// into is re-serialized, so each of its list entries maps to the entry in
+/// the base file or patch that last provided it.
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    files: HashMap<PathBuf, FileSource>,
+    /// Source positions of the on-disk files that patched entries came from
+    spans: HashMap<PathBuf, SpanIndex>,
+}
+
+#[derive(Debug, Clone)]
+struct FileSource {
+    /// On-disk file that last defined the whole file, or the first patch
+    /// when no base file exists
+    base: PathBuf,
+    /// For merged files: positions in the merged text
+    merged: Option<SpanIndex>,
+    /// For merged files: the origin of each list entry, e.g. `provinces.3`
+    /// -> (`mods/a/game/map/provinces.yaml`, `provinces.add.0`)
+    entries: HashMap<String, (PathBuf, String)>,
+}
+
+impl SourceMap {
+    /// `file` is defined whole by the on-disk file `source`.
+    pub fn whole(&mut self, file: &Path, source: &Path) {
+        self.files.insert(file.to_path_buf(), FileSource { base: source.to_path_buf(), merged: None, entries: HashMap::new() });
+    }
+
+    /// `file` was merged from patches into `text`; `entries` maps each list
+    /// entry of `text` to the on-disk file and YAML path it came from.
+    pub fn merged(&mut self, file: &Path, base: &Path, text: &str, entries: HashMap<String, (PathBuf, String)>, sources: &[(PathBuf, String)]) {
+        for source in entries.values().map(|(source, _)| source).chain([&base.to_path_buf()]) {
+            if !self.spans.contains_key(source) {
+                let text = sources.iter().find(|(p, _)| p == source).map_or("", |(_, s)| s.as_str());
+                self.spans.insert(source.clone(), SpanIndex::parse(text));
+            }
+        }
+        let merged = Some(SpanIndex::parse(text));
+        self.files.insert(file.to_path_buf(), FileSource { base: base.to_path_buf(), merged, entries });
+    }
+
+    /// On-disk path of the file loaded as `file`.
+    pub fn source_of(&self, file: &Path) -> Option<&Path> {
+        self.files.get(file).map(|f| f.base.as_path())
+    }
+
+    /// `loc` moved from the loaded file to the on-disk file it came from.
+    /// Positions that cannot be traced into a merged file fall back to its
+    /// base file without a line.
+    pub fn locate(&self, loc: Location) -> Location {
+        let Some(file) = self.files.get(&loc.path) else { return loc };
+        let Some(merged) = &file.merged else {
+            return Location { path: file.base.clone(), ..loc };
+        };
+        let span = match (loc.line, loc.column, loc.end_column) {
+            (Some(line), Some(column), Some(end)) => Some(Span { line, column, len: end.saturating_sub(column) }),
+            _ => None,
+        };
+        let traced = span.and_then(|span| {
+            let (path, is_key) = merged.path_of(span)?;
+            // `provinces.3.name` -> entry `provinces.3`, rest `.name`
+            let entry_end = path.match_indices('.').nth(1).map_or(path.len(), |(i, _)| i);
+            let (source, yaml_path) = match file.entries.get(&path[..entry_end]) {
+                Some((source, origin)) => (source, format!("{}{}", origin, &path[entry_end..])),
+                None => (&file.base, path.to_string()),
+            };
+            let spans = self.spans.get(source)?;
+            let span = if is_key { spans.key(&yaml_path) } else { spans.value(&yaml_path) }?;
+            Some(Location { label: loc.label.clone(), ..Location::span(source, span) })
+        });
+        traced.unwrap_or(Location { path: file.base.clone(), line: None, column: None, end_column: None, label: loc.label })
+    }
+
+    /// `diag` with every location moved to the on-disk files.
+    pub fn relocate(&self, mut diag: Diagnostic) -> Diagnostic {
+        diag.location = diag.location.map(|l| self.locate(l));
+        diag.related = diag.related.into_iter().map(|l| self.locate(l)).collect();
+        diag
+    }
+}
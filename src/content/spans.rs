@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::{Marker, TScalarStyle};
+
+/// Where a YAML node starts (1-based line and column) and how many
+/// characters of it to underline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    pub len: usize,
+}
+
+/// Source positions of every key and value in one YAML document, addressed
+/// by dotted path: `focuses.2.prerequisites.0` is the first prerequisite of
+/// the third focus.
+#[derive(Debug, Clone, Default)]
+pub struct SpanIndex {
+    values: HashMap<String, Span>,
+    keys: HashMap<String, Span>,
+    scalars: HashMap<String, String>,
+}
+
+impl SpanIndex {
+    /// Index `source`. Text that does not parse yields an empty index; the
+    /// typed parse reports the syntax error itself.
+    pub fn parse(source: &str) -> Self {
+        let mut builder = Builder::default();
+        let mut parser = Parser::new_from_str(source);
+        if parser.load(&mut builder, false).is_err() {
+            return SpanIndex::default();
+        }
+        builder.index
+    }
+
+    /// Span of the value at `path`.
+    pub fn value(&self, path: &str) -> Option<Span> {
+        self.values.get(path).copied()
+    }
+
+    /// Span of the key naming the value at `path`, falling back to the value
+    /// for list items.
+    pub fn key(&self, path: &str) -> Option<Span> {
+        self.keys.get(path).copied().or_else(|| self.value(path))
+    }
+
+    /// Path of the node at exactly `span`, and whether `span` is its key
+    /// rather than its value.
+    pub fn path_of(&self, span: Span) -> Option<(&str, bool)> {
+        let value = self.values.iter().find(|(_, s)| **s == span).map(|(path, _)| (path.as_str(), false));
+        value.or_else(|| self.keys.iter().find(|(_, s)| **s == span).map(|(path, _)| (path.as_str(), true)))
+    }
+
+    /// Paths of the items in list `list` whose `field` is the scalar `value`,
+    /// in document order: `entries("provinces", "id", "3")` -> `["provinces.7"]`.
+    pub fn entries(&self, list: &str, field: &str, value: &str) -> Vec<String> {
+        self.items(list)
+            .filter(|item| self.scalars.get(&format!("{}.{}", item, field)).is_some_and(|v| v == value))
+            .collect()
+    }
+
+    /// Path of the first item of the scalar list `list` equal to `value`.
+    pub fn find(&self, list: &str, value: &str) -> Option<String> {
+        self.items(list).find(|item| self.scalars.get(item).is_some_and(|v| v == value))
+    }
+
+    fn items<'a>(&'a self, list: &'a str) -> impl Iterator<Item = String> + 'a {
+        (0..)
+            .map(move |i| format!("{}.{}", list, i))
+            .take_while(|item| self.values.contains_key(item))
+    }
+}
+
+/// Convert a JSON pointer (`/focuses/2/days`) into a span path (`focuses.2.days`).
+pub fn pointer_to_path(pointer: &str) -> String {
+    pointer
+        .split('/')
+        .skip(1)
+        .map(|s| s.replace("~1", "/").replace("~0", "~"))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+enum Frame {
+    Seq { path: String, next: usize },
+    Map { path: String, key: Option<String>, fresh: bool },
+}
+
+#[derive(Default)]
+struct Builder {
+    index: SpanIndex,
+    stack: Vec<Frame>,
+}
+
+impl Builder {
+    /// Path of the node starting now, and whether it is a mapping key.
+    fn node_path(&mut self, scalar: Option<&str>, span: Span) -> (String, bool) {
+        match self.stack.last_mut() {
+            None => (String::new(), false),
+            Some(Frame::Seq { path, next }) => {
+                *next += 1;
+                (join(path, &(*next - 1).to_string()), false)
+            }
+            Some(Frame::Map { path, key, fresh }) => match key.take() {
+                Some(k) => (join(path, &k), false),
+                None => {
+                    // Block mappings are marked at their first `:`; start them at the first key instead
+                    if std::mem::take(fresh) {
+                        if let Some(start) = self.index.values.get_mut(path.as_str()) {
+                            if (span.line, span.column) < (start.line, start.column) {
+                                *start = Span { len: 1, ..span };
+                            }
+                        }
+                    }
+                    // Complex keys are not addressable; give them a placeholder
+                    let k = scalar.unwrap_or("?").to_string();
+                    let full = join(path, &k);
+                    *key = Some(k);
+                    (full, true)
+                }
+            },
+        }
+    }
+}
+
+impl MarkedEventReceiver for Builder {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        let len = match &event {
+            Event::Scalar(v, TScalarStyle::Plain, ..) => v.chars().count().max(1),
+            Event::Scalar(v, TScalarStyle::SingleQuoted | TScalarStyle::DoubleQuoted, ..) => v.chars().count() + 2,
+            _ => 1,
+        };
+        let span = Span { line: mark.line(), column: mark.col() + 1, len };
+        match event {
+            Event::Scalar(value, ..) => match self.node_path(Some(&value), span) {
+                (path, true) => {
+                    self.index.keys.insert(path, span);
+                }
+                (path, false) => {
+                    self.index.values.insert(path.clone(), span);
+                    self.index.scalars.insert(path, value);
+                }
+            },
+            Event::Alias(..) => {
+                if let (path, false) = self.node_path(None, span) {
+                    self.index.values.insert(path, span);
+                }
+            }
+            Event::SequenceStart(..) | Event::MappingStart(..) => {
+                let (path, is_key) = self.node_path(None, span);
+                if !is_key {
+                    self.index.values.insert(path.clone(), span);
+                }
+                self.stack.push(match event {
+                    Event::SequenceStart(..) => Frame::Seq { path, next: 0 },
+                    _ => Frame::Map { path, key: None, fresh: true },
+                });
+            }
+            Event::SequenceEnd | Event::MappingEnd => {
+                self.stack.pop();
+            }
+            _ => {}
+        }
+    }
+}
+
+fn join(parent: &str, seg: &str) -> String {
+    if parent.is_empty() {
+        seg.to_string()
+    } else {
+        format!("{}.{}", parent, seg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FOCUSES: &str = "\
+focuses:
+  - id: a
+    name: \"Alpha\"
+    days: 10
+  - { id: b, name: Beta, days: 5, prerequisites: [a, ghost] }
+";
+
+    #[test]
+    fn indexes_block_and_flow_nodes_by_path() {
+        let index = SpanIndex::parse(FOCUSES);
+        assert_eq!(index.value("focuses.0"), Some(Span { line: 2, column: 5, len: 1 }));
+        assert_eq!(index.value("focuses.1"), Some(Span { line: 5, column: 5, len: 1 }));
+        assert_eq!(index.value("focuses.0.id"), Some(Span { line: 2, column: 9, len: 1 }));
+        assert_eq!(index.key("focuses.0.days"), Some(Span { line: 4, column: 5, len: 4 }));
+        assert_eq!(index.value("focuses.0.name"), Some(Span { line: 3, column: 11, len: 7 }));
+        assert_eq!(index.value("focuses.1.prerequisites.1"), Some(Span { line: 5, column: 54, len: 5 }));
+        assert_eq!(index.entries("focuses", "id", "b"), vec!["focuses.1".to_string()]);
+        assert_eq!(index.find("focuses.1.prerequisites", "ghost").as_deref(), Some("focuses.1.prerequisites.1"));
+        assert_eq!(index.path_of(Span { line: 3, column: 11, len: 7 }), Some(("focuses.0.name", false)));
+        assert_eq!(index.path_of(Span { line: 2, column: 5, len: 2 }), Some(("focuses.0.id", true)));
+        assert_eq!(index.path_of(Span { line: 2, column: 5, len: 1 }), Some(("focuses.0", false)));
+        assert_eq!(pointer_to_path("/focuses/1/days"), "focuses.1.days");
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::content::diagnostics::Diagnostic;
+
+/// Inline suppression comments in content files:
+///
+/// ```yaml
+/// # validate: allow PAN024
+/// - { id: 9, name: Heligoland, pos: { x: 1100, y: 500 } }
+/// - { id: 4, name: Bonn, pos: { x: 1240, y: 640 } }  # validate: allow PAN025, PAN023
+/// ```
+///
+/// A comment after content silences the listed codes on its own line; a
+/// comment on a line by itself silences them on the next line. Comments are
+/// read from the files on disk, so they work in mod patches too, whose
+/// merged text loses them. Only warnings and info can be silenced this way;
+/// errors stay errors unless `validate.yaml` lowers them.
+#[derive(Debug, Clone, Default)]
+pub struct Suppressions {
+    lines: HashMap<PathBuf, HashMap<usize, Vec<String>>>,
+}
+
+impl Suppressions {
+    pub fn scan(files: &[(PathBuf, String)]) -> Self {
+        let mut lines: HashMap<PathBuf, HashMap<usize, Vec<String>>> = HashMap::new();
+        for (path, source) in files {
+            for (i, line) in source.lines().enumerate() {
+                let Some((before, codes)) = directive(line) else { continue };
+                // 1-based line the comment applies to
+                let target = if before.trim().is_empty() { i + 2 } else { i + 1 };
+                lines.entry(path.clone()).or_default().entry(target).or_default().extend(codes);
+            }
+        }
+        Suppressions { lines }
+    }
+
+    /// Whether a comment silences `diag` at its primary location. Errors are
+    /// never covered.
+    pub fn covers(&self, diag: &Diagnostic) -> bool {
+        if diag.is_error() {
+            return false;
+        }
+        let Some(loc) = &diag.location else { return false };
+        let Some(line) = loc.line else { return false };
+        self.lines
+            .get(&loc.path)
+            .and_then(|file| file.get(&line))
+            .is_some_and(|codes| codes.iter().any(|c| c == diag.code))
+    }
+}
+
+/// Text before a `# validate: allow ...` comment and the codes it lists.
+fn directive(line: &str) -> Option<(&str, Vec<String>)> {
+    line.match_indices('#').find_map(|(at, _)| {
+        let rest = line[at + 1..].trim_start().strip_prefix("validate:")?;
+        let codes = rest.trim_start().strip_prefix("allow")?;
+        let codes: Vec<String> = codes
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|c| !c.is_empty())
+            .map(str::to_string)
+            .collect();
+        Some((&line[..at], codes))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::config::ValidateConfig;
+    use crate::content::db::ContentDb;
+    use crate::content::diagnostics::{codes, Location};
+    use crate::content::loader::load_content;
+    use crate::content::validator::structural_diagnostics;
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn comments_cover_their_own_line_or_the_next() {
+        let path = PathBuf::from("game/map/provinces.yaml");
+        let source = "\
+provinces:
+  # validate: allow PAN024
+  - { id: 0, name: A, pos: { x: 0, y: 0 } }
+  - { id: 1, name: B, pos: { x: 0, y: 0 } }  # validate: allow PAN025, PAN023
+  - { id: 2, name: \"#3\", pos: { x: 5, y: 0 } }
+";
+        let suppressions = Suppressions::scan(&[(path.clone(), source.to_string())]);
+        let at = |code, line| Diagnostic::warning(code, "").at(Location::at(Path::new("game/map/provinces.yaml"), line, 5));
+
+        assert!(suppressions.covers(&at(codes::PROVINCE_WITHOUT_EDGES, 3)));
+        assert!(!suppressions.covers(&at(codes::SHARED_POSITION, 3)));
+        assert!(suppressions.covers(&at(codes::SHARED_POSITION, 4)));
+        assert!(suppressions.covers(&at(codes::MAP_ISLAND, 4)));
+        assert!(!suppressions.covers(&at(codes::PROVINCE_WITHOUT_EDGES, 5)));
+        assert!(!suppressions.covers(&Diagnostic::warning(codes::MAP_ISLAND, "").in_file(&path)));
+    }
+
+    #[test]
+    fn comments_do_not_silence_errors() {
+        let path = PathBuf::from("game/map/adjacency.yaml");
+        let source = "edges:\n  - { a: 0, b: 7 }  # validate: allow PAN020\n";
+        let suppressions = Suppressions::scan(&[(path.clone(), source.to_string())]);
+        let missing = Diagnostic::error(codes::EDGE_UNKNOWN_PROVINCE, "").at(Location::at(&path, 2, 5));
+        assert!(!suppressions.covers(&missing));
+
+        let (kept, suppressed) = ValidateConfig::default().apply(vec![missing], &[(path, source.to_string())]);
+        assert_eq!((kept.len(), suppressed), (1, 0));
+        assert!(kept[0].is_error());
+    }
+
+    #[test]
+    fn comments_in_mod_patches_survive_the_merge() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let root = dir.path();
+        fs::create_dir_all(root.join("game/map"))?;
+        fs::write(root.join("game/map/provinces.yaml"), "provinces:\n  - { id: 0, name: A, pos: { x: 0, y: 0 }, unowned: true }\n")?;
+        fs::write(root.join("game/map/adjacency.yaml"), "edges: []\n")?;
+        fs::create_dir_all(root.join("mods/a/game/map"))?;
+        fs::write(
+            root.join("mods/a/game/map/adjacency.yaml"),
+            "edges:\n  add:\n    # validate: allow PAN020\n    - { a: 0, b: 7 }\n    - { a: 0, b: 8 }\n",
+        )?;
+
+        let content = load_content(root)?;
+        let diags: Vec<Diagnostic> = structural_diagnostics(&ContentDb::from_files(&content.files))
+            .into_iter()
+            .filter(|d| d.code == codes::EDGE_UNKNOWN_PROVINCE)
+            .map(|d| content.source_map.relocate(d))
+            .collect();
+        assert_eq!(diags.len(), 2);
+        // PAN020 is an error, so only a warning can be silenced inline
+        let config: ValidateConfig = serde_yaml::from_str("rules:\n  PAN020: warning\n")?;
+        let (kept, suppressed) = config.apply(diags, &content.sources);
+        assert_eq!(suppressed, 1);
+        assert_eq!(kept[0].message, "Adjacency edge references missing province b=8");
+        assert_eq!(kept[0].location.as_ref().and_then(|l| l.line), Some(5));
+        Ok(())
+    }
+}
@@ -4,18 +4,43 @@ use serde_json::Value;
 use std::path::Path;
 use std::path::PathBuf;
 use petgraph::Graph;
-use petgraph::algo::is_cyclic_directed;
+use petgraph::algo::toposort;
 use petgraph::graph::NodeIndex;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use crate::core::{
-    province::ProvincesList,
-    adjacency::Adjacency,
-    focus::FocusTree,
-};
+use crate::content::config::StrictConfig;
+use crate::content::db::{owner_dir, ContentDb};
+use crate::content::diagnostics::{codes, yaml_error_location, Diagnostic, Location};
+use crate::content::focus_analysis::FocusAnalysis;
+use crate::content::schemas::{SchemaRegistry, REGISTRY_FILE};
+use crate::content::spans::{pointer_to_path, SpanIndex};
+use crate::core::characters::CharacterRole;
+use crate::core::effects::EffectRegistry;
+use crate::core::events::Trigger;
+use crate::core::focus::RawEffect;
+use crate::core::modifiers::{self, Stacking};
+use crate::core::politics::drift_key;
+use crate::core::units::BattalionStats;
 
 /// Validate a JSON value against a JSON Schema (schema as serde_json::Value)
 pub fn validate_value(schema: Value, doc: &Value) -> Result<()> {
+    let errors = schema_errors(schema, doc)?;
+    if !errors.is_empty() {
+        let mut msgs = String::new();
+        for (at, e) in errors {
+            if at.is_empty() {
+                msgs.push_str(&format!("{}\n", e));
+            } else {
+                msgs.push_str(&format!("{} (at {})\n", e, at));
+            }
+        }
+        anyhow::bail!("schema validation failed:\n{}", msgs);
+    }
+    Ok(())
+}
+
+/// Every schema violation in `doc` as `(JSON pointer of the offending value, message)`.
+pub fn schema_errors(schema: Value, doc: &Value) -> Result<Vec<(String, String)>> {
     // JSONSchema::compile requires the schema reference to have 'static lifetime.
     // For a CLI validator it's acceptable to leak the owned schema to obtain a
     // &'static Value for compilation. This avoids lifetime issues; the memory
@@ -27,14 +52,10 @@ pub fn validate_value(schema: Value, doc: &Value) -> Result<()> {
         .compile(schema_static)
         .with_context(|| "compiling schema")?;
     let res = compiled.validate(doc);
-    if let Err(errors) = res {
-        let mut msgs = String::new();
-        for e in errors {
-            msgs.push_str(&format!("{}\n", e));
-        }
-        anyhow::bail!("schema validation failed:\n{}", msgs);
-    }
-    Ok(())
+    Ok(match res {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors.map(|e| (e.instance_path.to_string(), e.to_string())).collect(),
+    })
 }
 
 /// Load a JSON schema from file path (expects JSON)
@@ -44,191 +65,771 @@ pub fn load_schema(path: &Path) -> Result<Value> {
     Ok(v)
 }
 
+/// Schema checks as coded diagnostics: one entry per checked file, holding
+/// every schema violation found in it (empty when the file passed).
+///
+/// Files are matched to schemas through `schemas/registry.yaml`. With
+/// `require_coverage`, files no pattern covers are reported as errors.
+pub fn schema_diagnostics(
+    schemas_dir: &Path,
+    files: &[(PathBuf, String)],
+    require_coverage: bool,
+) -> Vec<(PathBuf, Vec<Diagnostic>)> {
+    let mut results = Vec::new();
+    if !schemas_dir.exists() {
+        return results;
+    }
+    let registry = match SchemaRegistry::load(schemas_dir) {
+        Ok(r) => r,
+        Err(e) => {
+            let path = schemas_dir.join(REGISTRY_FILE);
+            results.push((path.clone(), vec![Diagnostic::error(codes::SCHEMA_UNREADABLE, format!("{:#}", e)).in_file(&path)]));
+            return results;
+        }
+    };
+    for (path, contents) in files {
+        let Some(json_schema_path) = registry.schema_for(path) else {
+            if require_coverage {
+                let msg = format!("No schema in {} covers {}", schemas_dir.join(REGISTRY_FILE).display(), path.display());
+                results.push((path.clone(), vec![Diagnostic::error(codes::SCHEMA_MISSING, msg).in_file(path)]));
+            }
+            continue;
+        };
+        let diags = match load_schema(&json_schema_path) {
+            Err(e) => vec![Diagnostic::error(codes::SCHEMA_UNREADABLE, format!("{:#}", e)).in_file(&json_schema_path)],
+            Ok(schema) => {
+                let doc: Result<Value, serde_yaml::Error> = match serde_json::from_str(contents) {
+                    Ok(v) => Ok(v),
+                    Err(_) => serde_yaml::from_str(contents),
+                };
+                match doc {
+                    Err(e) => {
+                        let msg = format!("parsing {}: {}", path.display(), e);
+                        vec![Diagnostic::error(codes::PARSE_FAILED, msg).at(yaml_error_location(path, &e))]
+                    }
+                    Ok(doc) => match schema_errors(schema, &doc) {
+                        Err(e) => vec![Diagnostic::error(codes::SCHEMA_UNREADABLE, format!("{:#}", e)).in_file(&json_schema_path)],
+                        Ok(errors) => {
+                            let spans = SpanIndex::parse(contents);
+                            errors
+                                .into_iter()
+                                .map(|(pointer, e)| {
+                                    let msg = format!("{} does not match schema: {}", path.display(), e);
+                                    let at = match spans.value(&pointer_to_path(&pointer)) {
+                                        Some(span) => Location::span(path, span),
+                                        None => Location::file(path),
+                                    };
+                                    Diagnostic::error(codes::SCHEMA_VIOLATION, msg).at(at)
+                                })
+                                .collect()
+                        }
+                    },
+                }
+            }
+        };
+        results.push((path.clone(), diags));
+    }
+    results
+}
+
 /// Structural validation for common game files that go beyond JSON Schema.
 /// These checks detect cross-file issues like missing province references,
 /// duplicated IDs, and cycles in focus trees.
 pub fn structural_validations(files: &[(PathBuf, String)]) -> Result<(), anyhow::Error> {
+    validate_db(&ContentDb::from_files(files))
+}
+
+/// Structural validation over an already-built [`ContentDb`].
+pub fn validate_db(db: &ContentDb) -> Result<(), anyhow::Error> {
     use std::fmt::Write;
-    let mut errors: Vec<String> = Vec::new();
-
-    // Collect provinces if present
-    let provinces_file = files.iter().find(|(p, _)| p.ends_with("provinces.yaml")).map(|(_, s)| s);
-    let adjacency_file = files.iter().find(|(p, _)| p.ends_with("adjacency.yaml")).map(|(_, s)| s);
-
-    // Validate province references
-    let mut province_ids = std::collections::HashSet::new();
-    if let Some(provinces) = provinces_file {
-        let def: ProvincesList = serde_yaml::from_str(provinces)?;
-        for p in def.provinces {
-            if !province_ids.insert(p.id) {
-                errors.push(format!("Duplicate province ID {}", p.id));
-            }
+    let errors = structural_errors(db);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        let mut msg = String::new();
+        writeln!(msg, "Structural validation failed:").unwrap();
+        for error in &errors {
+            writeln!(msg, "- {}", error).unwrap();
         }
+        Err(anyhow::anyhow!(msg))
     }
+}
+
+/// Every structural problem in `db`, one message per problem.
+pub fn structural_errors(db: &ContentDb) -> Vec<String> {
+    structural_diagnostics(db).into_iter().filter(Diagnostic::is_error).map(|d| d.message).collect()
+}
+
+/// Every structural problem in `db` as a coded [`Diagnostic`], located at
+/// the offending value where the source position is known.
+pub fn structural_diagnostics(db: &ContentDb) -> Vec<Diagnostic> {
+    // Parse failures and duplicate ids were collected while building the database
+    let mut diags = db.issues.clone();
 
     // Validate adjacency references
-    if let Some(adj) = adjacency_file {
-        let def: Adjacency = serde_yaml::from_str(adj)?;
-        for edge in def.edges {
-            if !province_ids.contains(&edge.a) || !province_ids.contains(&edge.b) {
-                errors.push(format!("Invalid province in adjacency edge {}↔{}", edge.a, edge.b));
+    for (edge, (path, i)) in db.edges.iter().zip(&db.edge_origins) {
+        for (side, id) in [("a", edge.a), ("b", edge.b)] {
+            if !db.provinces.contains_key(&id) {
+                let msg = format!("Adjacency edge references missing province {}={}", side, id);
+                let at = db.locate(path, &format!("edges.{}.{}", i, side)).with_label("no such province");
+                diags.push(Diagnostic::error(codes::EDGE_UNKNOWN_PROVINCE, msg).at(at));
             }
         }
     }
+    diags.extend(map_diagnostics(db));
 
-    // Focus tree cycle detection
-    for (path, contents) in files {
-        if path.ends_with("focus_tree.yaml") {
-            let tree: FocusTree = serde_yaml::from_str(contents)?;
-            
-            // Build graph of focus dependencies
-            let mut g = Graph::new();
-            let mut node_map = HashMap::new();
-
-            // Create nodes
-            for focus in &tree.focuses {
-                let idx = g.add_node(focus.id.clone());
-                node_map.insert(focus.id.clone(), idx);
+    // Countries: check province references and capital validity
+    for (tag, c) in &db.countries {
+        let p = &db.country_files[tag];
+        for (i, idu) in c.owned_provinces.iter().flatten().enumerate() {
+            if !db.provinces.contains_key(idu) {
+                let msg = format!("Country {} references unknown owned_province {}", p.display(), idu);
+                let at = db.locate(p, &format!("owned_provinces.{}", i)).with_label("no such province");
+                diags.push(Diagnostic::error(codes::OWNED_UNKNOWN_PROVINCE, msg).at(at));
             }
+        }
+        for (i, idu) in c.controlled_provinces.iter().flatten().enumerate() {
+            if !db.provinces.contains_key(idu) {
+                let msg = format!("Country {} references unknown controlled_province {}", p.display(), idu);
+                let at = db.locate(p, &format!("controlled_provinces.{}", i)).with_label("no such province");
+                diags.push(Diagnostic::error(codes::CONTROLLED_UNKNOWN_PROVINCE, msg).at(at));
+            }
+        }
+        if let Some(cap) = c.capital {
+            if !db.provinces.contains_key(&cap) {
+                let msg = format!("Country {} has capital referencing unknown province {}", p.display(), cap);
+                let at = db.locate(p, "capital").with_label("no such province");
+                diags.push(Diagnostic::error(codes::CAPITAL_UNKNOWN_PROVINCE, msg).at(at));
+            }
+        }
+    }
 
-            // Add edges for prerequisites
-            for focus in &tree.focuses {
-                let from = node_map[&focus.id];
-                for prereq in &focus.prerequisites {
-                    if let Some(&to) = node_map.get(prereq) {
-                        g.add_edge(to, from, ());
-                    } else {
-                        errors.push(format!("Focus {} has unknown prerequisite {}", focus.id, prereq));
-                    }
-                }
+    diags.extend(ownership_diagnostics(db));
+
+    // Focus trees: prereqs exist and DAG check
+    for (tag, ft) in &db.focus_trees {
+        let p = &db.focus_tree_files[tag];
+        let mut seen: HashMap<&str, usize> = HashMap::new();
+        for (i, f) in ft.focuses.iter().enumerate() {
+            if let Some(first) = seen.insert(f.id.as_str(), i) {
+                let msg = format!("Focus tree {} contains duplicate focus id {}", p.display(), f.id);
+                let again = db.locate(p, &format!("focuses.{}.id", i)).with_label("defined again here");
+                let original = db.locate(p, &format!("focuses.{}.id", first)).with_label("first defined here");
+                diags.push(Diagnostic::error(codes::DUPLICATE_FOCUS, msg).at(again).related(original));
+            }
+        }
+        let prereqs = ft.focuses.iter().map(|f| (f.id.as_str(), f.prerequisites.as_slice()));
+        for (id, pre) in unknown_prerequisites(prereqs.clone()) {
+            let msg = format!("Focus {} in {} has unknown prerequisite {}", id, p.display(), pre);
+            let at = prerequisite_location(db, p, "focuses", id, pre);
+            diags.push(Diagnostic::error(codes::FOCUS_UNKNOWN_PREREQUISITE, msg).at(at));
+        }
+        if let Some(id) = prerequisite_cycle(prereqs) {
+            let msg = format!("Focus tree {} contains cycles", p.display());
+            let at = entry_location(db, p, "focuses", id, "prerequisites").with_label(format!("{} depends on itself", id));
+            diags.push(Diagnostic::error(codes::FOCUS_CYCLE, msg).at(at));
+        }
+
+        let analysis = FocusAnalysis::of(ft);
+        for (id, other) in &analysis.unknown_exclusions {
+            let msg = format!("Focus {} in {} is mutually exclusive with unknown focus {}", id, p.display(), other);
+            let at = list_item_location(db, p, "focuses", id, "mutually_exclusive", other).with_label("no such focus");
+            diags.push(Diagnostic::error(codes::FOCUS_UNKNOWN_EXCLUSION, msg).at(at));
+        }
+        for (id, other) in &analysis.asymmetric_exclusions {
+            let msg = format!("Focus {} excludes {}, but {} does not exclude {}", id, other, other, id);
+            let at = list_item_location(db, p, "focuses", id, "mutually_exclusive", other)
+                .with_label(format!("not listed in {}'s mutually_exclusive", other));
+            let missing = entry_location(db, p, "focuses", other, "id").with_label(format!("add {} here", id));
+            diags.push(Diagnostic::warning(codes::FOCUS_ASYMMETRIC_EXCLUSION, msg).at(at).related(missing));
+        }
+        for f in analysis.unreachable() {
+            let (a, b) = f.blocked_by.as_ref().expect("unreachable focuses have a blocking pair");
+            let msg = format!("Focus {} can never be taken: it requires both {} and {}, which are mutually exclusive", f.id, a, b);
+            let at = entry_location(db, p, "focuses", &f.id, "id").with_label("unreachable");
+            diags.push(Diagnostic::warning(codes::FOCUS_UNREACHABLE, msg).at(at));
+        }
+    }
+
+    diags.extend(research_diagnostics(db));
+
+    // Game speeds are seconds per day, so zero or negative would stall the clock
+    if let (Some(table), Some(p)) = (&db.speeds, &db.speeds_file) {
+        for (i, spd) in table.speeds.iter().enumerate() {
+            if !(*spd > 0.0 && spd.is_finite()) {
+                let msg = format!("Speed {} in {} is {}; speeds must be positive seconds per day", i + 1, p.display(), spd);
+                let at = db.locate(p, &format!("speeds.{}", i)).with_label("not a positive number");
+                diags.push(Diagnostic::error(codes::INVALID_SPEED, msg).at(at));
             }
+        }
+    }
+
+    diags.extend(effect_diagnostics(db));
+    diags.extend(event_diagnostics(db));
+    diags.extend(idea_diagnostics(db));
+    diags.extend(ideology_diagnostics(db));
+    diags.extend(character_diagnostics(db));
+    diags
+}
+
+/// Technologies follow the same prerequisite rules as focuses, and research
+/// queues only name technologies that exist.
+fn research_diagnostics(db: &ContentDb) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+    let tech_prereqs = db.techs.values().map(|t| (t.id.as_str(), t.prerequisites.as_slice()));
+    for (id, pre) in unknown_prerequisites(tech_prereqs.clone()) {
+        let msg = format!("Technology {} has unknown prerequisite {}", id, pre);
+        let at = prerequisite_location(db, &db.tech_files[id], "technologies", id, pre);
+        diags.push(Diagnostic::error(codes::TECH_UNKNOWN_PREREQUISITE, msg).at(at));
+    }
+    if let Some(id) = prerequisite_cycle(tech_prereqs) {
+        let at = entry_location(db, &db.tech_files[id], "technologies", id, "prerequisites")
+            .with_label(format!("{} depends on itself", id));
+        diags.push(Diagnostic::error(codes::TECH_CYCLE, "Technology tree contains cycles").at(at));
+    }
 
-            // Check for cycles
-            if is_cyclic_directed(&g) {
-                errors.push(format!("Cycle detected in focus tree {}", path.display()));
+    // Research queues only reference known technologies
+    for (tag, r) in &db.research {
+        let p = &db.research_files[tag];
+        for (i, tech) in r.research_queue.iter().enumerate() {
+            if db.tech(tech).is_none() {
+                let msg = format!("Research queue of {} references unknown technology {}", tag, tech);
+                let at = db.locate(p, &format!("research_queue.{}", i)).with_label("no such technology");
+                diags.push(Diagnostic::error(codes::RESEARCH_UNKNOWN_TECH, msg).at(at));
             }
         }
     }
+    diags
+}
 
-    // province_ids already populated above; skip second duplicate parsing block to avoid duplicate errors
+/// Every effect in focuses, technologies and event options names a
+/// registered effect.
+fn effect_diagnostics(db: &ContentDb) -> Vec<Diagnostic> {
+    let registry = EffectRegistry::core();
+    let mut diags = Vec::new();
+    let mut check = |path: &Path, list: &str, id: &str, field: &str, effects: &[RawEffect]| {
+        let entry = db.spans.get(path).and_then(|s| s.entries(list, "id", id).into_iter().next());
+        for (i, effect) in effects.iter().enumerate() {
+            let at = match &entry {
+                Some(entry) => db.locate(path, &format!("{}.{}.{}", entry, field, i)),
+                None => Location::file(path),
+            };
+            if !registry.contains(&effect.kind) {
+                let msg = format!("{} uses unknown effect type {}", id, effect.kind);
+                diags.push(Diagnostic::error(codes::UNKNOWN_EFFECT, msg).at(at.with_label("no such effect")));
+            } else if let Some(problem) = effect_param_problem(db, effect) {
+                let msg = format!("{} effect of {}: {}", effect.kind, id, problem);
+                diags.push(Diagnostic::error(codes::INVALID_EFFECT_PARAMS, msg).at(at.with_label("invalid parameters")));
+            } else if effect.kind == "add_modifier" {
+                let key = effect.params.get("key").and_then(|k| k.as_str()).unwrap_or_default();
+                let multiply = effect.params.get("stacking").and_then(|s| s.as_str()) == Some("multiply");
+                let stacking = if multiply { Stacking::Multiply } else { Stacking::Add };
+                diags.extend(zero_base_multiply(db, &format!("add_modifier effect of {}", id), key, stacking, at));
+            }
+        }
+    };
+    for (tag, tree) in &db.focus_trees {
+        for f in &tree.focuses {
+            check(&db.focus_tree_files[tag], "focuses", &f.id, "effects", &f.effects);
+        }
+    }
+    for (id, t) in &db.techs {
+        check(&db.tech_files[id], "technologies", id, "effects", &t.effects);
+    }
+    for (id, e) in &db.events {
+        for (i, option) in e.options.iter().enumerate() {
+            check(&db.event_files[id], "events", id, &format!("options.{}.effects", i), &option.effects);
+        }
+    }
+    diags
+}
 
-    if let Some(adj_s) = adjacency_file {
-        match serde_yaml::from_str::<serde_json::Value>(adj_s) {
-            Ok(v) => {
-                if let Some(arr) = v.get("edges").and_then(|p| p.as_array()) {
-                    for item in arr {
-                        let a = item.get("a").and_then(|x| x.as_u64()).map(|x| x as u32);
-                        let b = item.get("b").and_then(|x| x.as_u64()).map(|x| x as u32);
-                        match (a, b) {
-                            (Some(a), Some(b)) => {
-                                if !province_ids.contains(&a) { errors.push(format!("Adjacency edge references missing province a={}", a)); }
-                                if !province_ids.contains(&b) { errors.push(format!("Adjacency edge references missing province b={}", b)); }
-                            }
-                            _ => errors.push("Adjacency edge missing numeric a/b".to_string()),
-                        }
-                    }
-                } else {
-                    errors.push("adjacency.yaml missing 'edges' array".to_string());
+/// Parameters the simulation would reject or silently ignore, for effects
+/// whose parameters name other content.
+fn effect_param_problem(db: &ContentDb, effect: &RawEffect) -> Option<String> {
+    let param = |key: &str| effect.params.get(key);
+    match effect.kind.as_str() {
+        "unit_stat_mod" => {
+            let unit = param("unit").and_then(|u| u.as_str());
+            match unit {
+                None => return Some("needs a `unit`".into()),
+                Some(u) if db.battalion(u).is_none() => return Some(format!("unknown unit {}", u)),
+                Some(_) => {}
+            }
+            let stats = effect.params.as_mapping().into_iter().flatten().filter_map(|(k, _)| k.as_str()).filter(|k| *k != "unit");
+            let unknown: Vec<&str> = stats.filter(|k| BattalionStats::default().get(k).is_none()).collect();
+            (!unknown.is_empty()).then(|| format!("unknown stat {} (expected one of {})", unknown.join(", "), BattalionStats::NAMES.join(", ")))
+        }
+        "add_modifier" => {
+            let (Some(key), Some(_)) = (param("key").and_then(|k| k.as_str()), param("value").and_then(|v| v.as_f64())) else {
+                return Some("needs a `key` and a numeric `value`".into());
+            };
+            if !known_modifier_key(db, key) {
+                return Some(format!("unknown modifier key {}", key));
+            }
+            // Provinces only add to their owner's country-wide values
+            match param("province").map(|p| p.as_u64()) {
+                Some(None) => return Some("`province` must be a province id".into()),
+                Some(Some(id)) if db.province(id as u32).is_none() => return Some(format!("unknown province {}", id)),
+                Some(Some(_)) if !modifiers::COUNTRY_KEYS.contains(&key) => {
+                    return Some(format!("province modifiers need a country key (one of {}), not {}", modifiers::COUNTRY_KEYS.join(", "), key))
                 }
+                _ => {}
+            }
+            match param("stacking").map(|s| s.as_str()) {
+                None | Some(Some("add" | "multiply")) => None,
+                Some(_) => Some("`stacking` must be add or multiply".into()),
+            }
+        }
+        "unlock_battalion" => match param("battalion").and_then(|b| b.as_str()) {
+            None => Some("needs a `battalion`".into()),
+            Some(id) if db.battalion(id).is_none() => Some(format!("unknown battalion {}", id)),
+            Some(_) => None,
+        },
+        "add_idea" | "remove_idea" => match param("idea").and_then(|i| i.as_str()) {
+            None => Some("needs an `idea`".into()),
+            Some(id) if db.idea(id).is_none() => Some(format!("unknown idea {}", id)),
+            Some(_) => None,
+        },
+        "add_character" | "remove_character" | "promote_character" => match param("character").and_then(|c| c.as_str()) {
+            None => Some("needs a `character`".into()),
+            Some(id) if db.character(id).is_none() => Some(format!("unknown character {}", id)),
+            Some(_) => None,
+        },
+        "add_popularity" | "set_ruling_party" => {
+            let needs_value = effect.kind == "add_popularity";
+            match param("ideology").and_then(|i| i.as_str()) {
+                None => Some("needs an `ideology`".into()),
+                Some(id) if db.ideology(id).is_none() => Some(format!("unknown ideology {}", id)),
+                Some(_) if needs_value && param("value").and_then(|v| v.as_f64()).is_none() => Some("needs a numeric `value`".into()),
+                Some(_) => None,
             }
-            Err(e) => errors.push(format!("parsing adjacency.yaml: {}", e)),
         }
+        _ => None,
     }
+}
 
-    // Countries: check province references and capital validity
-    for (p, s) in files.iter().filter(|(p, _)| p.ends_with("country.yaml")) {
-        match serde_yaml::from_str::<serde_json::Value>(s) {
-            Ok(v) => {
-                if let Some(owned) = v.get("owned_provinces").and_then(|x| x.as_array()) {
-                    for idv in owned {
-                        if let Some(id) = idv.as_u64() {
-                            let idu = id as u32;
-                            if !province_ids.contains(&idu) {
-                                errors.push(format!("Country {} references unknown owned_province {}", p.display(), idu));
-                            }
-                        }
-                    }
-                }
-                if let Some(controlled) = v.get("controlled_provinces").and_then(|x| x.as_array()) {
-                    for idv in controlled {
-                        if let Some(id) = idv.as_u64() {
-                            let idu = id as u32;
-                            if !province_ids.contains(&idu) {
-                                errors.push(format!("Country {} references unknown controlled_province {}", p.display(), idu));
-                            }
-                        }
-                    }
-                }
-                if let Some(cap) = v.get("capital").and_then(|x| x.as_u64()).map(|x| x as u32) {
-                    if !province_ids.contains(&cap) {
-                        errors.push(format!("Country {} has capital referencing unknown province {}", p.display(), cap));
-                    }
+/// Events are scheduled one way or the other and their triggers name
+/// content that exists.
+fn event_diagnostics(db: &ContentDb) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+    let written = script_writes(db);
+    for (id, e) in &db.events {
+        let p = &db.event_files[id];
+        if e.date.is_some() == e.mean_time_to_happen.is_some() {
+            let msg = format!("Event {} needs exactly one of date or mean_time_to_happen", id);
+            let at = entry_location(db, p, "events", id, "id").with_label("no schedule, or both");
+            diags.push(Diagnostic::error(codes::EVENT_WITHOUT_SCHEDULE, msg).at(at));
+        }
+        for t in e.trigger.iter().flat_map(Trigger::walk) {
+            let unknown = match t {
+                Trigger::Tag(tag) if db.country(tag).is_none() => Some(format!("country {}", tag)),
+                Trigger::HasCompletedFocus(f) if !db.focus_trees.values().any(|tree| tree.find(f).is_some()) => {
+                    Some(format!("focus {}", f))
                 }
+                Trigger::HasTech(tech) if db.tech(tech).is_none() => Some(format!("technology {}", tech)),
+                Trigger::HasIdea(idea) if db.idea(idea).is_none() => Some(format!("idea {}", idea)),
+                Trigger::RulingIdeology(i) if db.ideology(i).is_none() => Some(format!("ideology {}", i)),
+                Trigger::PopularityAtLeast(c) if db.ideology(&c.ideology).is_none() => Some(format!("ideology {}", c.ideology)),
+                _ => None,
+            };
+            if let Some(what) = unknown {
+                let msg = format!("Trigger of event {} references unknown {}", id, what);
+                let at = entry_location(db, p, "events", id, "trigger").with_label("unknown reference");
+                diags.push(Diagnostic::error(codes::TRIGGER_UNKNOWN_REFERENCE, msg).at(at));
+            }
+            // Usually a typo, or a storyline step that was never written
+            let read = match t {
+                Trigger::HasFlag(f) => Some(("flag", false, f)),
+                Trigger::HasGlobalFlag(f) => Some(("flag", true, f)),
+                Trigger::VariableAtLeast(c) => Some(("variable", false, &c.variable)),
+                Trigger::GlobalVariableAtLeast(c) => Some(("variable", true, &c.variable)),
+                _ => None,
+            };
+            if let Some((what, global, name)) = read.filter(|(what, global, name)| !written.contains(&(*what, *global, name.as_str()))) {
+                let scope = if global { "global " } else { "" };
+                let msg = format!("Trigger of event {} checks {}{} {}, which no effect sets", id, scope, what, name);
+                let at = entry_location(db, p, "events", id, "trigger").with_label("never set");
+                diags.push(Diagnostic::warning(codes::SCRIPT_VALUE_NEVER_SET, msg).at(at));
             }
-            Err(e) => errors.push(format!("parsing {}: {}", p.display(), e)),
         }
     }
+    diags
+}
 
-    // Focus trees: prereqs exist and DAG check
-    for (p, s) in files.iter().filter(|(p, _)| p.ends_with("focus_tree.yaml") || p.ends_with("focus_tree.yml")) {
-        match serde_yaml::from_str::<crate::core::focus::FocusTree>(s) {
-            Ok(ft) => {
-                let ids: std::collections::HashSet<_> = ft.focuses.iter().map(|f| f.id.clone()).collect();
-                // unique ids checked by set size vs vec len
-                if ids.len() != ft.focuses.len() {
-                    errors.push(format!("Focus tree {} contains duplicate focus ids", p.display()));
-                }
-                // build graph prereq -> id using a petgraph::Graph
-                let mut g = Graph::<String, ()>::new();
-                let mut node_map: HashMap<String, NodeIndex> = HashMap::new();
-                for f in &ft.focuses {
-                    let idx = g.add_node(f.id.clone());
-                    node_map.insert(f.id.clone(), idx);
+/// Idea modifiers use keys the simulation reads, on units that exist.
+fn idea_diagnostics(db: &ContentDb) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+    for (id, idea) in &db.ideas {
+        let p = &db.idea_files[id];
+        for (i, m) in idea.modifiers.iter().enumerate() {
+            let problem = if !known_modifier_key(db, &m.key) {
+                Some((codes::UNKNOWN_MODIFIER_KEY, format!("Idea {} has unknown modifier key {}", id, m.key), "key", "unknown key"))
+            } else {
+                m.unit
+                    .as_ref()
+                    .filter(|u| db.battalion(u).is_none())
+                    .map(|u| (codes::IDEA_UNKNOWN_UNIT, format!("Idea {} modifies unknown unit {}", id, u), "unit", "unknown unit"))
+            };
+            if let Some((code, msg, field, label)) = problem {
+                let at = entry_location(db, p, "ideas", id, &format!("modifiers.{}.{}", i, field)).with_label(label);
+                diags.push(Diagnostic::error(code, msg).at(at));
+            }
+            let at = entry_location(db, p, "ideas", id, &format!("modifiers.{}.stacking", i));
+            diags.extend(zero_base_multiply(db, &format!("Idea {}", id), &m.key, m.stacking, at));
+        }
+    }
+    diags
+}
+
+/// Keys the simulation reads: the fixed ones plus each ideology's drift.
+fn known_modifier_key(db: &ContentDb, key: &str) -> bool {
+    modifiers::is_known_key(key) || db.ideologies.keys().any(|id| drift_key(id) == key)
+}
+
+/// Warning for a multiplicative modifier on a key evaluated from zero,
+/// which multiplying leaves at zero.
+fn zero_base_multiply(db: &ContentDb, owner: &str, key: &str, stacking: Stacking, at: Location) -> Option<Diagnostic> {
+    let zero_base = modifiers::ZERO_BASE_KEYS.contains(&key) || db.ideologies.keys().any(|id| drift_key(id) == key);
+    (zero_base && stacking == Stacking::Multiply).then(|| {
+        let msg = format!("{} multiplies {}, which starts at zero; use stacking: add", owner, key);
+        Diagnostic::warning(codes::MULTIPLY_ON_ZERO_BASE, msg).at(at.with_label("has no effect"))
+    })
+}
+
+/// Countries are ruled by, and give support to, ideologies that exist.
+fn ideology_diagnostics(db: &ContentDb) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+    for (tag, c) in &db.countries {
+        let p = &db.country_files[tag];
+        if let Some(id) = c.ideology.as_ref().filter(|id| db.ideology(id).is_none()) {
+            let msg = format!("Country {} is ruled by unknown ideology {}", tag, id);
+            diags.push(Diagnostic::error(codes::UNKNOWN_IDEOLOGY, msg).at(db.locate(p, "ideology").with_label("no such ideology")));
+        }
+        for id in c.popularity.iter().flat_map(|p| p.keys()).filter(|id| db.ideology(id).is_none()) {
+            let msg = format!("Popularity of {} names unknown ideology {}", tag, id);
+            let at = db.locate_key(p, &format!("popularity.{}", id)).with_label("no such ideology");
+            diags.push(Diagnostic::error(codes::UNKNOWN_IDEOLOGY, msg).at(at));
+        }
+    }
+    diags
+}
+
+/// Characters name ideologies and traits that exist and modify keys the
+/// simulation reads; trait modifiers apply to divisions, so they must be
+/// battalion stats. Countries with characters have a leader for their
+/// ruling ideology.
+fn character_diagnostics(db: &ContentDb) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+    let mut zero_base = Vec::new();
+    for (id, c) in &db.characters {
+        let p = &db.character_files[id];
+        if let Some(ideology) = c.ideology.as_ref().filter(|i| db.ideology(i).is_none()) {
+            let at = entry_location(db, p, "characters", id, "ideology").with_label("no such ideology");
+            let msg = format!("Character {} leads unknown ideology {}", id, ideology);
+            diags.push(Diagnostic::error(codes::CHARACTER_UNKNOWN_IDEOLOGY, msg).at(at));
+        }
+        for t in c.traits.iter().filter(|t| db.character_trait(t).is_none()) {
+            let at = list_item_location(db, p, "characters", id, "traits", t).with_label("no such trait");
+            let msg = format!("Character {} has unknown trait {}", id, t);
+            diags.push(Diagnostic::error(codes::CHARACTER_UNKNOWN_TRAIT, msg).at(at));
+        }
+        for (i, m) in c.modifiers.iter().enumerate() {
+            if !known_modifier_key(db, &m.key) {
+                let at = entry_location(db, p, "characters", id, &format!("modifiers.{}.key", i)).with_label("unknown key");
+                let msg = format!("Character {} has unknown modifier key {}", id, m.key);
+                diags.push(Diagnostic::error(codes::CHARACTER_UNKNOWN_MODIFIER_KEY, msg).at(at));
+            }
+            let at = entry_location(db, p, "characters", id, &format!("modifiers.{}.stacking", i));
+            zero_base.extend(zero_base_multiply(db, &format!("Character {}", id), &m.key, m.stacking, at));
+        }
+    }
+    if let Some(p) = &db.traits_file {
+        for (id, t) in &db.traits {
+            for (i, m) in t.modifiers.iter().enumerate().filter(|(_, m)| !BattalionStats::NAMES.contains(&m.key.as_str())) {
+                let at = entry_location(db, p, "traits", id, &format!("modifiers.{}.key", i)).with_label("not a battalion stat");
+                let msg = format!("Trait {} modifies {}, which is not a battalion stat", id, m.key);
+                diags.push(Diagnostic::error(codes::TRAIT_NOT_BATTALION_STAT, msg).at(at));
+            }
+        }
+    }
+    diags.extend(zero_base);
+
+    for (tag, country) in &db.countries {
+        let Some(ruling) = &country.ideology else { continue };
+        let mut characters = db.characters_of(tag).peekable();
+        let Some(first) = characters.peek() else { continue };
+        let p = &db.character_files[&first.id];
+        if !characters.any(|c| c.role == CharacterRole::Leader && c.ideology.as_ref().is_none_or(|i| i == ruling)) {
+            let msg = format!("{} has no leader for its ruling ideology {}", tag, ruling);
+            diags.push(Diagnostic::warning(codes::COUNTRY_WITHOUT_LEADER, msg).at(Location::file(p)));
+        }
+    }
+    diags
+}
+
+/// `(flag or variable, global, name)` for every script value some effect writes.
+fn script_writes(db: &ContentDb) -> HashSet<(&'static str, bool, &str)> {
+    let focus_effects = db.focus_trees.values().flat_map(|t| &t.focuses).flat_map(|f| &f.effects);
+    let tech_effects = db.techs.values().flat_map(|t| &t.effects);
+    let event_effects = db.events.values().flat_map(|e| &e.options).flat_map(|o| &o.effects);
+    focus_effects
+        .chain(tech_effects)
+        .chain(event_effects)
+        .filter_map(|e| {
+            let (what, key) = match e.kind.as_str() {
+                "set_flag" => ("flag", "flag"),
+                "set_variable" | "add_to_variable" => ("variable", "variable"),
+                _ => return None,
+            };
+            let global = e.params.get("global").and_then(|g| g.as_bool()).unwrap_or(false);
+            Some((what, global, e.params.get(key)?.as_str()?))
+        })
+        .collect()
+}
+
+/// Map shape: loops and repeated edges are errors; provinces that cannot be
+/// reached from the main landmass or that share a position are warnings.
+fn map_diagnostics(db: &ContentDb) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+
+    // Each undirected edge once; a reversed copy counts as a duplicate
+    let mut seen: HashMap<(u32, u32), usize> = HashMap::new();
+    for (i, (edge, (path, index))) in db.edges.iter().zip(&db.edge_origins).enumerate() {
+        let at = db.locate(path, &format!("edges.{}", index));
+        if edge.a == edge.b {
+            let msg = format!("Adjacency edge connects province {} to itself", edge.a);
+            diags.push(Diagnostic::error(codes::EDGE_SELF_LOOP, msg).at(at.with_label("self-loop")));
+            continue;
+        }
+        let key = (edge.a.min(edge.b), edge.a.max(edge.b));
+        if let Some(&first) = seen.get(&key) {
+            let original = &db.edges[first];
+            let reversed = if original.a == edge.a { "" } else { "reversed " };
+            let msg = format!("Adjacency edge {}-{} is a {}duplicate of edge {}-{}", edge.a, edge.b, reversed, original.a, original.b);
+            let (first_path, first_index) = &db.edge_origins[first];
+            let original = db.locate(first_path, &format!("edges.{}", first_index)).with_label("first listed here");
+            diags.push(Diagnostic::error(codes::DUPLICATE_EDGE, msg).at(at.with_label("listed again here")).related(original));
+        } else {
+            seen.insert(key, i);
+        }
+    }
+
+    // Provinces with no neighbour but themselves
+    let isolated = |id: u32| db.neighbours(id).iter().all(|n| *n == id);
+    for p in db.provinces.values().filter(|p| !p.island && isolated(p.id)) {
+        let msg = format!("Province {} ({}) has no adjacency edges", p.id, p.name);
+        let at = province_location(db, p.id, "id").with_label("not connected to any province");
+        diags.push(Diagnostic::warning(codes::PROVINCE_WITHOUT_EDGES, msg).at(at));
+    }
+
+    // Connected components; everything outside the largest one is an island
+    let mut component: HashMap<u32, usize> = HashMap::new();
+    let mut components: Vec<Vec<u32>> = Vec::new();
+    for &start in db.provinces.keys() {
+        if component.contains_key(&start) {
+            continue;
+        }
+        let mut members = vec![start];
+        component.insert(start, components.len());
+        let mut next = 0;
+        while let Some(&id) = members.get(next) {
+            next += 1;
+            for &n in db.neighbours(id) {
+                if db.provinces.contains_key(&n) && !component.contains_key(&n) {
+                    component.insert(n, components.len());
+                    members.push(n);
                 }
-                for f in &ft.focuses {
-                    for pre in &f.prerequisites {
-                        if !ids.contains(pre) {
-                            errors.push(format!("Focus {} in {} has unknown prerequisite {}", f.id, p.display(), pre));
-                        } else {
-                            let a = node_map.get(pre).unwrap();
-                            let b = node_map.get(&f.id).unwrap();
-                            g.add_edge(*a, *b, ());
-                        }
-                    }
+            }
+        }
+        components.push(members);
+    }
+    // Ties go to the component loaded first
+    let mainland = components.iter().enumerate().max_by_key(|(i, c)| (c.len(), std::cmp::Reverse(*i))).map(|(i, _)| i);
+    for (i, members) in components.iter().enumerate() {
+        let marked = members.iter().all(|id| db.provinces[id].island);
+        // Lone provinces without edges were reported above
+        if Some(i) == mainland || marked || (members.len() == 1 && isolated(members[0])) {
+            continue;
+        }
+        let ids: Vec<String> = members.iter().map(u32::to_string).collect();
+        let msg = format!(
+            "Provinces {} are not connected to the rest of the map; set `island: true` on them if intended",
+            ids.join(", ")
+        );
+        let at = province_location(db, members[0], "id").with_label("unreachable from the main map");
+        diags.push(Diagnostic::warning(codes::MAP_ISLAND, msg).at(at));
+    }
+
+    // Two provinces drawn on top of each other
+    let mut positions: HashMap<(i32, i32), u32> = HashMap::new();
+    for p in db.provinces.values() {
+        if let Some(&other) = positions.get(&(p.pos.x, p.pos.y)) {
+            let msg = format!("Provinces {} and {} share position ({}, {})", other, p.id, p.pos.x, p.pos.y);
+            let at = province_location(db, p.id, "pos").with_label("same position");
+            let original = province_location(db, other, "pos").with_label(format!("position of province {}", other));
+            diags.push(Diagnostic::warning(codes::SHARED_POSITION, msg).at(at).related(original));
+        } else {
+            positions.insert((p.pos.x, p.pos.y), p.id);
+        }
+    }
+
+    diags
+}
+
+/// Ownership: every province has exactly one owner unless marked
+/// `unowned`, capitals lie in the owner's territory, control only covers
+/// owned provinces, and tags are well-formed and match their directory.
+fn ownership_diagnostics(db: &ContentDb) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+
+    // First country in load order to claim each province, and where
+    let mut owners: HashMap<u32, (&str, usize)> = HashMap::new();
+    for (tag, c) in &db.countries {
+        let p = &db.country_files[tag];
+        for (i, id) in c.owned_provinces.iter().flatten().enumerate() {
+            if !db.provinces.contains_key(id) {
+                continue;
+            }
+            match owners.get(id) {
+                Some(&(first, _)) if first == tag => {}
+                Some(&(first, j)) => {
+                    let msg = format!("Province {} is owned by both {} and {}", id, first, tag);
+                    let at = db.locate(p, &format!("owned_provinces.{}", i)).with_label(format!("claimed by {} here", tag));
+                    let original = db
+                        .locate(&db.country_files[first], &format!("owned_provinces.{}", j))
+                        .with_label(format!("already owned by {}", first));
+                    diags.push(Diagnostic::error(codes::CONTESTED_PROVINCE, msg).at(at).related(original));
                 }
-                if is_cyclic_directed(&g) {
-                    errors.push(format!("Focus tree {} contains cycles", p.display()));
+                None => {
+                    owners.insert(*id, (tag, i));
                 }
             }
-            Err(e) => errors.push(format!("parsing {}: {}", p.display(), e)),
         }
     }
 
-    // Units: battalions unique IDs
-    for (p, s) in files.iter().filter(|(p, _)| p.to_string_lossy().ends_with("battalions.yaml") || p.to_string_lossy().ends_with("battalions.yml")) {
-        match serde_yaml::from_str::<crate::core::units::Battalions>(s) {
-            Ok(b) => {
-                let ids: std::collections::HashSet<_> = b.battalions.iter().map(|b| b.id.clone()).collect();
-                if ids.len() != b.battalions.len() {
-                    errors.push(format!("Battalions {} contains duplicate battalion ids", p.display()));
-                }
+    for p in db.provinces.values() {
+        match (owners.get(&p.id), p.unowned) {
+            (None, false) => {
+                let msg = format!("Province {} ({}) has no owner; list it in a country's owned_provinces or set `unowned: true`", p.id, p.name);
+                let at = province_location(db, p.id, "id").with_label("no country owns this province");
+                diags.push(Diagnostic::error(codes::UNOWNED_PROVINCE, msg).at(at));
+            }
+            (Some(&(tag, i)), true) => {
+                let msg = format!("Province {} ({}) is marked unowned but {} owns it", p.id, p.name, tag);
+                let at = db.locate(&db.country_files[tag], &format!("owned_provinces.{}", i)).with_label(format!("owned by {} here", tag));
+                let marked = province_location(db, p.id, "unowned").with_label("marked unowned here");
+                diags.push(Diagnostic::error(codes::OWNED_UNOWNED_PROVINCE, msg).at(at).related(marked));
             }
-            Err(e) => errors.push(format!("parsing {}: {}", p.display(), e)),
+            _ => {}
         }
     }
 
-    if errors.is_empty() {
-        Ok(())
-    } else {
-        let mut msg = String::new();
-        writeln!(msg, "Structural validation failed:").unwrap();
-        for error in &errors {
-            writeln!(msg, "- {}", error).unwrap();
+    for (tag, c) in &db.countries {
+        let p = &db.country_files[tag];
+        if let Some(cap) = c.capital.filter(|cap| db.provinces.contains_key(cap)) {
+            if !c.owned_provinces.iter().flatten().any(|id| *id == cap) {
+                let msg = format!("Capital {} of {} is not one of its owned_provinces", cap, tag);
+                let at = db.locate(p, "capital").with_label(format!("not owned by {}", tag));
+                diags.push(Diagnostic::error(codes::CAPITAL_NOT_OWNED, msg).at(at));
+            }
         }
-        Err(anyhow::anyhow!(msg))
+        for (i, id) in c.controlled_provinces.iter().flatten().enumerate() {
+            if db.provinces.contains_key(id) && !owners.contains_key(id) {
+                let msg = format!("{} controls province {}, which no country owns", tag, id);
+                let at = db.locate(p, &format!("controlled_provinces.{}", i)).with_label("owned by no country");
+                diags.push(Diagnostic::error(codes::CONTROLLED_UNOWNED_PROVINCE, msg).at(at));
+            }
+        }
+
+        // CountryTag only parses three characters, and per-country files are keyed by directory
+        if tag.len() != 3 || !tag.bytes().all(|b| b.is_ascii_uppercase()) {
+            let msg = format!("Country tag {:?} in {} must be three uppercase letters", tag, p.display());
+            diags.push(Diagnostic::error(codes::INVALID_COUNTRY_TAG, msg).at(db.locate(p, "tag").with_label("invalid tag")));
+        } else if owner_dir(p) != *tag {
+            let msg = format!("Country tag {} does not match its directory {}", tag, p.parent().unwrap_or(p).display());
+            let at = db.locate(p, "tag").with_label(format!("expected {}", owner_dir(p)));
+            diags.push(Diagnostic::error(codes::COUNTRY_TAG_MISMATCH, msg).at(at));
+        }
+    }
+
+    diags
+}
+
+/// Strict mode: every key the typed parse skipped, unless `config` allows
+/// it as an extension field for that content type.
+pub fn strict_diagnostics(db: &ContentDb, config: &StrictConfig) -> Vec<Diagnostic> {
+    db.unknown_fields
+        .iter()
+        .filter(|f| !config.allows(f))
+        .map(|f| {
+            let msg = format!("Unknown field `{}` at {} in {}", f.name(), f.field, f.path.display());
+            let at = db.locate_key(&f.path, &f.field).with_label("unknown field");
+            Diagnostic::error(codes::UNKNOWN_FIELD, msg).at(at)
+        })
+        .collect()
+}
+
+/// Location of `field` of the entry with `id` in `list`, falling back to the file.
+fn entry_location(db: &ContentDb, path: &Path, list: &str, id: &str, field: &str) -> Location {
+    let entry = db.spans.get(path).and_then(|s| s.entries(list, "id", id).into_iter().next());
+    match entry {
+        Some(entry) => db.locate_key(path, &format!("{}.{}", entry, field)),
+        None => Location::file(path),
     }
 }
 
+/// Location of `field` of province `id` in its provinces file.
+fn province_location(db: &ContentDb, id: u32, field: &str) -> Location {
+    entry_location(db, &db.province_files[&id], "provinces", &id.to_string(), field)
+}
+
+/// Location of the prerequisite `pre` of entry `id`, labelled as unknown.
+fn prerequisite_location(db: &ContentDb, path: &Path, list: &str, id: &str, pre: &str) -> Location {
+    list_item_location(db, path, list, id, "prerequisites", pre).with_label("unknown prerequisite")
+}
+
+/// Location of `value` in the list `field` of entry `id`, falling back to
+/// the field and then the file.
+fn list_item_location(db: &ContentDb, path: &Path, list: &str, id: &str, field: &str, value: &str) -> Location {
+    let spans = db.spans.get(path);
+    let entry = spans.and_then(|s| s.entries(list, "id", id).into_iter().next());
+    let index = entry.as_ref().and_then(|e| spans?.find(&format!("{}.{}", e, field), value));
+    match (index, entry) {
+        (Some(item), _) => db.locate(path, &item),
+        (None, Some(entry)) => db.locate_key(path, &format!("{}.{}", entry, field)),
+        (None, None) => Location::file(path),
+    }
+}
+
+/// `(id, prerequisite)` pairs whose prerequisite is not one of the ids.
+fn unknown_prerequisites<'a>(
+    items: impl Iterator<Item = (&'a str, &'a [String])> + Clone,
+) -> Vec<(&'a str, &'a str)> {
+    let ids: std::collections::HashSet<&str> = items.clone().map(|(id, _)| id).collect();
+    items
+        .flat_map(|(id, pres)| pres.iter().map(move |pre| (id, pre.as_str())))
+        .filter(|(_, pre)| !ids.contains(pre))
+        .collect()
+}
+
+/// Build a petgraph of prerequisite -> id edges and return an id on a cycle, if any.
+fn prerequisite_cycle<'a>(items: impl Iterator<Item = (&'a str, &'a [String])> + Clone) -> Option<&'a str> {
+    let mut g = Graph::<&str, ()>::new();
+    let mut node_map: HashMap<&str, NodeIndex> = HashMap::new();
+    for (id, _) in items.clone() {
+        node_map.entry(id).or_insert_with(|| g.add_node(id));
+    }
+    for (id, pres) in items {
+        for pre in pres {
+            if let Some(&a) = node_map.get(pre.as_str()) {
+                g.add_edge(a, node_map[id], ());
+            }
+        }
+    }
+    toposort(&g, None).err().map(|cycle| g[cycle.node_id()])
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -253,4 +854,271 @@ mod tests {
         assert!(res.is_err());
         Ok(())
     }
+
+    #[test]
+    fn structural_diagnostics_carry_codes_and_files() {
+        let files = vec![
+            (PathBuf::from("game/map/provinces.yaml"), "provinces:\n  - { id: 0, name: A, pos: { x: 0, y: 0 }, unowned: true }\n".to_string()),
+            (PathBuf::from("game/map/adjacency.yaml"), "edges:\n  - { a: 0, b: 9 }\n".to_string()),
+            (PathBuf::from("game/countries/GER/country.yaml"), "tag: GER\nname: Germany\ncapital: 4\n".to_string()),
+        ];
+        let diags = structural_diagnostics(&ContentDb::from_files(&files));
+        let found: Vec<(&str, &Path)> = diags
+            .iter()
+            .map(|d| (d.code, d.location.as_ref().unwrap().path.as_path()))
+            .collect();
+        assert_eq!(found, vec![
+            (codes::EDGE_UNKNOWN_PROVINCE, Path::new("game/map/adjacency.yaml")),
+            (codes::CAPITAL_UNKNOWN_PROVINCE, Path::new("game/countries/GER/country.yaml")),
+        ]);
+    }
+
+    #[test]
+    fn map_checks_flag_loops_duplicates_islands_and_overlaps() {
+        let provinces = "provinces:
+  - { id: 0, name: A, pos: { x: 0, y: 0 } }
+  - { id: 1, name: B, pos: { x: 10, y: 0 } }
+  - { id: 2, name: C, pos: { x: 50, y: 50 } }
+  - { id: 3, name: D, pos: { x: 60, y: 50 } }
+  - { id: 4, name: E, pos: { x: 10, y: 0 } }
+  - { id: 5, name: F, pos: { x: 90, y: 90 }, island: true }
+  - { id: 6, name: G, pos: { x: 20, y: 0 } }
+";
+        let edges = "edges:\n  - { a: 0, b: 1 }\n  - { a: 1, b: 6 }\n  - { a: 1, b: 0 }\n  - { a: 2, b: 3 }\n  - { a: 4, b: 4 }\n";
+        let files = vec![
+            (PathBuf::from("game/map/provinces.yaml"), provinces.to_string()),
+            (PathBuf::from("game/map/adjacency.yaml"), edges.to_string()),
+        ];
+        let mut diags = structural_diagnostics(&ContentDb::from_files(&files));
+        // No countries here, so every province is also unowned
+        diags.retain(|d| d.code != codes::UNOWNED_PROVINCE);
+        let found: Vec<(&str, bool, Option<usize>)> = diags
+            .iter()
+            .map(|d| (d.code, d.is_error(), d.location.as_ref().and_then(|l| l.line)))
+            .collect();
+        assert_eq!(found, vec![
+            (codes::DUPLICATE_EDGE, true, Some(4)),
+            (codes::EDGE_SELF_LOOP, true, Some(6)),
+            (codes::PROVINCE_WITHOUT_EDGES, false, Some(6)),
+            (codes::MAP_ISLAND, false, Some(4)),
+            (codes::SHARED_POSITION, false, Some(6)),
+        ]);
+        assert_eq!(diags[0].message, "Adjacency edge 1-0 is a reversed duplicate of edge 0-1");
+        assert_eq!(diags[4].related[0].line, Some(3));
+    }
+
+    #[test]
+    fn ownership_checks_cover_owners_capitals_control_and_tags() {
+        let country = |tag: &str, rest: &str| {
+            (PathBuf::from(format!("game/countries/{}/country.yaml", &tag[..3])), format!("tag: {}\nname: X\n{}", tag, rest))
+        };
+        let files = vec![
+            (
+                PathBuf::from("game/map/provinces.yaml"),
+                "provinces:\n  - { id: 0, name: A, pos: { x: 0, y: 0 }, island: true }\n  - { id: 1, name: B, pos: { x: 1, y: 0 }, island: true }\n  - { id: 2, name: C, pos: { x: 2, y: 0 }, island: true, unowned: true }\n  - { id: 3, name: D, pos: { x: 3, y: 0 }, island: true }\n".to_string(),
+            ),
+            country("GER", "owned_provinces: [0, 1]\ncontrolled_provinces: [3]\ncapital: 2\n"),
+            country("FRA", "owned_provinces: [1, 2]\ncapital: 1\n"),
+            country("ITAL", "owned_provinces: []\n"),
+            (PathBuf::from("game/countries/ENG/country.yaml"), "tag: GBR\nname: X\n".to_string()),
+        ];
+        let diags = structural_diagnostics(&ContentDb::from_files(&files));
+        let found: Vec<(&str, &str)> = diags.iter().map(|d| (d.code, d.message.as_str())).collect();
+        assert_eq!(found, vec![
+            (codes::CONTESTED_PROVINCE, "Province 1 is owned by both GER and FRA"),
+            (codes::OWNED_UNOWNED_PROVINCE, "Province 2 (C) is marked unowned but FRA owns it"),
+            (codes::UNOWNED_PROVINCE, "Province 3 (D) has no owner; list it in a country's owned_provinces or set `unowned: true`"),
+            (codes::CAPITAL_NOT_OWNED, "Capital 2 of GER is not one of its owned_provinces"),
+            (codes::CONTROLLED_UNOWNED_PROVINCE, "GER controls province 3, which no country owns"),
+            (codes::INVALID_COUNTRY_TAG, "Country tag \"ITAL\" in game/countries/ITA/country.yaml must be three uppercase letters"),
+            (codes::COUNTRY_TAG_MISMATCH, "Country tag GBR does not match its directory game/countries/ENG"),
+        ]);
+        assert_eq!(diags[0].location.as_ref().and_then(|l| l.line), Some(3));
+        assert_eq!(diags[0].related[0].path, Path::new("game/countries/GER/country.yaml"));
+    }
+
+    #[test]
+    fn focus_errors_render_with_carets_under_the_value() {
+        let path = PathBuf::from("game/countries/GER/focus_tree.yaml");
+        let source = "focuses:\n  - id: a\n    name: A\n    days: 10\n    prerequisites: [ghost]\n";
+        let files = vec![(path.clone(), source.to_string())];
+        let diags = structural_diagnostics(&ContentDb::from_files(&files));
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].code, codes::FOCUS_UNKNOWN_PREREQUISITE);
+        assert_eq!(diags[0].render(&files), "\
+error[PAN041]: Focus a in game/countries/GER/focus_tree.yaml has unknown prerequisite ghost
+ --> game/countries/GER/focus_tree.yaml:5:21
+  |
+5 |     prerequisites: [ghost]
+  |                     ^^^^^ unknown prerequisite
+");
+    }
+
+    #[test]
+    fn research_checks_cover_prerequisites_cycles_and_queues() {
+        let techs = "technologies:
+  - { id: a, name: A, days: 10, prerequisites: [ghost] }
+  - { id: b, name: B, days: 10, prerequisites: [c] }
+  - { id: c, name: C, days: 10, prerequisites: [b] }
+";
+        let files = vec![
+            (PathBuf::from("game/research/technologies.yaml"), techs.to_string()),
+            (PathBuf::from("game/countries/GER/country.yaml"), "tag: GER\nname: Germany\n".to_string()),
+            (PathBuf::from("game/countries/GER/research.yaml"), "research_slots: 2\nresearch_queue: [a, radar]\n".to_string()),
+        ];
+        let found: Vec<(&str, String, Option<usize>)> = research_diagnostics(&ContentDb::from_files(&files))
+            .into_iter()
+            .map(|d| (d.code, d.message, d.location.and_then(|l| l.line)))
+            .collect();
+        assert_eq!(found, vec![
+            (codes::TECH_UNKNOWN_PREREQUISITE, "Technology a has unknown prerequisite ghost".to_string(), Some(2)),
+            (codes::TECH_CYCLE, "Technology tree contains cycles".to_string(), Some(4)),
+            (codes::RESEARCH_UNKNOWN_TECH, "Research queue of GER references unknown technology radar".to_string(), Some(2)),
+        ]);
+    }
+
+    #[test]
+    fn event_checks_cover_schedules_effects_and_trigger_references() {
+        let events = "events:
+  - id: a
+    title: A
+    trigger: { all: [{ tag: GER }, { has_tech: radar }, { has_flag: ready }, { has_global_flag: ready }] }
+    options: [{ name: OK, effects: [{ type: add_pp, amount: 5 }, { type: add_ufo }, { type: set_flag, flag: ready }, { type: unit_stat_mod, unit: infantry, atack: 1 }] }]
+  - id: b
+    title: B
+    date: 1936-02-01
+    mean_time_to_happen: 30
+    options: [{ name: OK, effects: [{ type: unlock_battalion, battalion: engineers }] }]
+  - { id: a, title: Again, date: 1936-02-01, options: [{ name: OK }] }
+";
+        let files = vec![
+            (PathBuf::from("game/countries/GER/country.yaml"), "tag: GER\nname: Germany\n".to_string()),
+            (PathBuf::from("game/events/news.yaml"), events.to_string()),
+        ];
+        let diags = structural_diagnostics(&ContentDb::from_files(&files));
+        let found: Vec<(&str, &str, Option<usize>)> = diags
+            .iter()
+            .map(|d| (d.code, d.message.as_str(), d.location.as_ref().and_then(|l| l.line)))
+            .collect();
+        assert_eq!(found, vec![
+            (codes::DUPLICATE_EVENT, "Event id a is defined more than once", Some(11)),
+            (codes::UNKNOWN_EFFECT, "a uses unknown effect type add_ufo", Some(5)),
+            (codes::INVALID_EFFECT_PARAMS, "unit_stat_mod effect of a: unknown unit infantry", Some(5)),
+            (codes::INVALID_EFFECT_PARAMS, "unlock_battalion effect of b: unknown battalion engineers", Some(10)),
+            (codes::EVENT_WITHOUT_SCHEDULE, "Event a needs exactly one of date or mean_time_to_happen", Some(2)),
+            (codes::TRIGGER_UNKNOWN_REFERENCE, "Trigger of event a references unknown technology radar", Some(4)),
+            (codes::SCRIPT_VALUE_NEVER_SET, "Trigger of event a checks global flag ready, which no effect sets", Some(4)),
+            (codes::EVENT_WITHOUT_SCHEDULE, "Event b needs exactly one of date or mean_time_to_happen", Some(6)),
+        ]);
+    }
+
+    #[test]
+    fn idea_checks_cover_modifier_keys_units_and_references() {
+        let ideas = "ideas:
+  - id: drive
+    name: Drive
+    modifiers: [{ key: manpower, value: 1 }, { key: manpowr, value: 1 }, { key: soft_attack, value: 1, unit: infantry }, { key: research_speed, value: 0.1, stacking: multiply }]
+";
+        let events = "events:
+  - id: e
+    title: E
+    date: 1936-01-01
+    trigger: { not: { has_idea: zeal } }
+    options: [{ name: OK, effects: [{ type: add_idea, idea: drive }, { type: remove_idea, idea: zeal }, { type: add_modifier, key: hp, value: 1 }] }]
+  - id: p
+    title: P
+    date: 1936-01-01
+    options: [{ name: OK, effects: [{ type: add_modifier, key: civ_factories, value: 1, province: 9 }, { type: add_modifier, key: hp, value: 1, province: 0 }] }]
+";
+        let files = vec![
+            (PathBuf::from("game/ideas/ideas.yaml"), ideas.to_string()),
+            (PathBuf::from("game/events/news.yaml"), events.to_string()),
+            (PathBuf::from("game/map/provinces.yaml"), "provinces:\n  - { id: 0, name: A, pos: { x: 0, y: 0 }, island: true, unowned: true }\n".to_string()),
+        ];
+        let diags = structural_diagnostics(&ContentDb::from_files(&files));
+        let found: Vec<(&str, &str)> = diags.iter().map(|d| (d.code, d.message.as_str())).collect();
+        assert_eq!(found, vec![
+            (codes::INVALID_EFFECT_PARAMS, "remove_idea effect of e: unknown idea zeal"),
+            (codes::INVALID_EFFECT_PARAMS, "add_modifier effect of p: unknown province 9"),
+            (
+                codes::INVALID_EFFECT_PARAMS,
+                "add_modifier effect of p: province modifiers need a country key (one of civ_factories, mil_factories, manpower, research_speed, political_power_gain), not hp",
+            ),
+            (codes::TRIGGER_UNKNOWN_REFERENCE, "Trigger of event e references unknown idea zeal"),
+            (codes::UNKNOWN_MODIFIER_KEY, "Idea drive has unknown modifier key manpowr"),
+            (codes::IDEA_UNKNOWN_UNIT, "Idea drive modifies unknown unit infantry"),
+            (codes::MULTIPLY_ON_ZERO_BASE, "Idea drive multiplies research_speed, which starts at zero; use stacking: add"),
+        ]);
+    }
+
+    #[test]
+    fn ideology_checks_cover_countries_effects_triggers_and_drift_keys() {
+        let events = "events:
+  - id: e
+    title: E
+    date: 1936-01-01
+    trigger: { ruling_ideology: monarchy }
+    options: [{ name: OK, effects: [{ type: add_modifier, key: fascism_drift, value: 0.01 }, { type: add_popularity, ideology: fascism }] }]
+";
+        let files = vec![
+            (PathBuf::from("game/ideologies.yaml"), "ideologies:\n  - { id: fascism, name: F }\n".to_string()),
+            (
+                PathBuf::from("game/countries/GER/country.yaml"),
+                "tag: GER\nname: Germany\nideology: nazism\npopularity: { fascism: 1, junta: 1 }\n".to_string(),
+            ),
+            (PathBuf::from("game/events/news.yaml"), events.to_string()),
+        ];
+        let diags = structural_diagnostics(&ContentDb::from_files(&files));
+        let found: Vec<(&str, &str)> = diags.iter().map(|d| (d.code, d.message.as_str())).collect();
+        assert_eq!(found, vec![
+            (codes::INVALID_EFFECT_PARAMS, "add_popularity effect of e: needs a numeric `value`"),
+            (codes::TRIGGER_UNKNOWN_REFERENCE, "Trigger of event e references unknown ideology monarchy"),
+            (codes::UNKNOWN_IDEOLOGY, "Country GER is ruled by unknown ideology nazism"),
+            (codes::UNKNOWN_IDEOLOGY, "Popularity of GER names unknown ideology junta"),
+        ]);
+    }
+
+    #[test]
+    fn character_checks_cover_references_trait_keys_and_leaders() {
+        let characters = "characters:
+  - { id: d, name: D, role: leader, ideology: democratic }
+  - { id: g, name: G, role: general, traits: [panzer, flanker] }
+  - { id: a, name: A, role: advisor, cost: 50, modifiers: [{ key: civ_factorys, value: 1 }] }
+";
+        let files = vec![
+            (PathBuf::from("game/ideologies.yaml"), "ideologies:\n  - { id: fascism, name: F }\n".to_string()),
+            (PathBuf::from("game/traits.yaml"), "traits:\n  - { id: panzer, name: P, modifiers: [{ key: research_speed, value: 1 }] }\n".to_string()),
+            (PathBuf::from("game/countries/GER/country.yaml"), "tag: GER\nname: Germany\nideology: fascism\n".to_string()),
+            (PathBuf::from("game/countries/GER/characters.yaml"), characters.to_string()),
+        ];
+        let diags = structural_diagnostics(&ContentDb::from_files(&files));
+        let found: Vec<(&str, &str)> = diags.iter().map(|d| (d.code, d.message.as_str())).collect();
+        assert_eq!(found, vec![
+            (codes::CHARACTER_UNKNOWN_IDEOLOGY, "Character d leads unknown ideology democratic"),
+            (codes::CHARACTER_UNKNOWN_TRAIT, "Character g has unknown trait flanker"),
+            (codes::CHARACTER_UNKNOWN_MODIFIER_KEY, "Character a has unknown modifier key civ_factorys"),
+            (codes::TRAIT_NOT_BATTALION_STAT, "Trait panzer modifies research_speed, which is not a battalion stat"),
+            (codes::COUNTRY_WITHOUT_LEADER, "GER has no leader for its ruling ideology fascism"),
+        ]);
+    }
+
+    #[test]
+    fn strict_mode_reports_unknown_fields_except_allowed_ones() -> Result<()> {
+        let files = vec![(
+            PathBuf::from("game/units/battalions.yaml"),
+            "battalions:\n  - id: inf\n    name: Infantry\n    wieght: 2\n    x_author: me\n    stats: { hp: 1, armour: 3 }\n".to_string(),
+        )];
+        let db = ContentDb::from_files(&files);
+        let config: StrictConfig = serde_yaml::from_str("allow:\n  battalions: [\"x_*\"]\n")?;
+        let diags = strict_diagnostics(&db, &config);
+        let found: Vec<(&str, Option<usize>)> = diags
+            .iter()
+            .map(|d| (d.message.as_str(), d.location.as_ref().and_then(|l| l.line)))
+            .collect();
+        assert_eq!(found, vec![
+            ("Unknown field `wieght` at battalions.0.wieght in game/units/battalions.yaml", Some(4)),
+            ("Unknown field `armour` at battalions.0.stats.armour in game/units/battalions.yaml", Some(6)),
+        ]);
+        Ok(())
+    }
 }
@@ -0,0 +1,58 @@
+use anyhow::{Context, Result};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::Mutex;
+
+/// Watches `game/` and `mods/` under a content root for YAML/JSON changes.
+pub struct ContentWatcher {
+    _watcher: RecommendedWatcher,
+    // Mutex so the watcher can live in a Bevy resource (Receiver is not Sync)
+    events: Mutex<Receiver<notify::Result<notify::Event>>>,
+    roots: Vec<PathBuf>,
+}
+
+impl ContentWatcher {
+    pub fn new(root: &Path) -> Result<Self> {
+        let (tx, rx) = channel();
+        let mut watcher = notify::recommended_watcher(move |res| {
+            // The receiver only goes away when the watcher is dropped
+            let _ = tx.send(res);
+        })
+        .context("creating content file watcher")?;
+
+        let mut roots = Vec::new();
+        for dir in ["game", "mods"] {
+            let path = root.join(dir);
+            if path.exists() {
+                watcher
+                    .watch(&path, RecursiveMode::Recursive)
+                    .with_context(|| format!("watching {}", path.display()))?;
+                roots.push(path);
+            }
+        }
+        Ok(ContentWatcher { _watcher: watcher, events: Mutex::new(rx), roots })
+    }
+
+    /// Directories being watched.
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    /// Drain pending events and report whether any content file changed.
+    /// Bursts of events from one save collapse into a single `true`.
+    pub fn poll_changed(&self) -> bool {
+        let Ok(rx) = self.events.lock() else { return false };
+        let mut changed = false;
+        while let Ok(res) = rx.try_recv() {
+            if let Ok(event) = res {
+                changed |= !event.kind.is_access() && event.paths.iter().any(|p| is_content_file(p));
+            }
+        }
+        changed
+    }
+}
+
+fn is_content_file(path: &Path) -> bool {
+    matches!(path.extension().and_then(|e| e.to_str()), Some("yaml" | "yml" | "json"))
+}
@@ -0,0 +1,146 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::core::modifiers::Stacking;
+
+/// Highest general skill level.
+pub const MAX_SKILL: u32 = 5;
+
+/// Multiplicative bonus a general's divisions get to attack and defense
+/// per skill level.
+pub const SKILL_BONUS: f64 = 0.05;
+
+/// Stats a general's skill improves.
+pub const SKILL_STATS: [&str; 3] = ["soft_attack", "hard_attack", "defense"];
+
+/// A person from `game/countries/<TAG>/characters.yaml`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CharacterDef {
+    /// Unique character identifier across all countries
+    pub id: String,
+    /// Display name of the character
+    pub name: String,
+    pub role: CharacterRole,
+    /// Leaders: the ideology they lead; a leader without one serves any government
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ideology: Option<String>,
+    /// Advisors: political power it takes to hire them
+    #[serde(default)]
+    #[schemars(range(min = 0.0))]
+    pub cost: f64,
+    /// Leaders and advisors: country modifiers while they are in office
+    #[serde(default)]
+    pub modifiers: Vec<CharacterModifier>,
+    /// Generals: skill level, improving their divisions' attack and defense
+    #[serde(default = "default_skill")]
+    #[schemars(range(min = 1, max = 5))]
+    pub skill: u32,
+    /// Generals: ids from `game/traits.yaml`
+    #[serde(default)]
+    pub traits: Vec<String>,
+}
+
+fn default_skill() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CharacterRole {
+    Leader,
+    Advisor,
+    General,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CharacterModifier {
+    /// Modifier key; general traits use battalion stats like `soft_attack`
+    pub key: String,
+    pub value: f64,
+    #[serde(default)]
+    pub stacking: Stacking,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+pub struct CharacterFile { pub characters: Vec<CharacterDef> }
+
+/// A general trait from `game/traits.yaml`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TraitDef {
+    /// Unique trait identifier
+    pub id: String,
+    /// Display name of the trait
+    pub name: String,
+    /// Modifiers for the divisions of a general with this trait
+    #[serde(default)]
+    pub modifiers: Vec<CharacterModifier>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+pub struct TraitFile { pub traits: Vec<TraitDef> }
+
+/// The people a country has and the posts they hold.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CountryCharacters {
+    /// Everyone at the country's disposal
+    pub roster: BTreeSet<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub leader: Option<String>,
+    /// Hired advisors
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub advisors: BTreeSet<String>,
+    /// Divisions each general commands
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub commands: BTreeMap<String, BTreeSet<u64>>,
+}
+
+impl CountryCharacters {
+    /// General commanding `division`, if any.
+    pub fn commander(&self, division: u64) -> Option<&str> {
+        self.commands.iter().find(|(_, divisions)| divisions.contains(&division)).map(|(general, _)| general.as_str())
+    }
+
+    /// Put `general` in command of `divisions`, taking them from whoever led
+    /// them before. An empty list relieves the general.
+    pub fn assign(&mut self, general: &str, divisions: &BTreeSet<u64>) {
+        for led in self.commands.values_mut() {
+            led.retain(|d| !divisions.contains(d));
+        }
+        self.commands.insert(general.to_string(), divisions.clone());
+        self.commands.retain(|_, led| !led.is_empty());
+    }
+
+    /// Drop `id` from the roster and every post it holds.
+    pub fn remove(&mut self, id: &str) {
+        self.roster.remove(id);
+        self.advisors.remove(id);
+        self.commands.remove(id);
+        if self.leader.as_deref() == Some(id) {
+            self.leader = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn divisions_have_one_commander_at_a_time() {
+        let mut c = CountryCharacters::default();
+        c.roster.extend(["rundstedt".to_string(), "bock".to_string()]);
+        c.assign("rundstedt", &BTreeSet::from([1, 2, 3]));
+        c.assign("bock", &BTreeSet::from([3, 4]));
+        assert_eq!(c.commander(2), Some("rundstedt"));
+        assert_eq!(c.commander(3), Some("bock"));
+        assert_eq!(c.commander(5), None);
+
+        c.assign("rundstedt", &BTreeSet::new());
+        assert_eq!(c.commander(1), None);
+        assert!(!c.commands.contains_key("rundstedt"));
+
+        c.remove("bock");
+        assert!(c.commands.is_empty() && !c.roster.contains("bock"));
+    }
+}
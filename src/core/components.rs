@@ -2,18 +2,6 @@ use bevy::prelude::*;
 use serde::{Serialize, Deserialize};
 use crate::core::types::*;
 
-#[derive(Component, Serialize, Deserialize, Debug, Clone)]
-pub struct FocusProgress {
-    pub focus_id: FocusId,
-    pub days_remaining: u32,
-}
-
-#[derive(Component, Serialize, Deserialize, Debug, Clone)]
-pub struct ResearchProgress {
-    pub tech_id: String,
-    pub days_remaining: u32,
-}
-
 // Province Components
 #[derive(Component, Serialize, Deserialize, Debug, Clone)]
 pub struct ProvinceOwnership {
@@ -51,9 +39,3 @@ pub struct DivisionComponent {
     pub id: DivisionId,
     pub location: ProvinceId,
 }
-
-#[derive(Component, Serialize, Deserialize, Debug, Clone)]
-pub struct DivisionMovement {
-    pub to: ProvinceId,
-    pub days_left: u32,
-}
\ No newline at end of file
@@ -1,16 +1,34 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Color { pub r: f32, pub g: f32, pub b: f32 }
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Color {
+    #[schemars(range(min = 0.0, max = 1.0))]
+    pub r: f32,
+    #[schemars(range(min = 0.0, max = 1.0))]
+    pub g: f32,
+    #[schemars(range(min = 0.0, max = 1.0))]
+    pub b: f32,
+}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CountryDef {
+    /// Three-letter country tag (e.g., 'GER', 'FRA')
+    #[schemars(regex(pattern = r"^[A-Z]{3}$"))]
     pub tag: String,
+    /// Full country name
     pub name: String,
     pub color: Option<Color>,
+    /// Ruling ideology, an id from `game/ideologies.yaml`
     pub ideology: Option<String>,
+    /// Starting support per ideology, relative: values are scaled to sum to 1
+    pub popularity: Option<BTreeMap<String, f64>>,
+    /// Starting manpower, factories and research slots
+    #[schemars(with = "Option<serde_json::Map<String, serde_json::Value>>")]
     pub resources: Option<serde_json::Value>,
     pub owned_provinces: Option<Vec<u32>>,
     pub controlled_provinces: Option<Vec<u32>>,
+    /// Province ID of the capital
     pub capital: Option<u32>,
 }
@@ -1,61 +1,275 @@
+#[cfg(feature = "bevy")]
 use bevy::prelude::*;
 use std::collections::HashMap;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde_json::Value;
-use crate::core::types::CountryTag;
+use crate::content::db::ContentDb;
+use crate::core::focus::RawEffect;
+use crate::core::modifiers::{Modifier, ModifierScope, ModifierSource, Stacking};
+use crate::core::script::ScriptState;
+use crate::core::sim::{CountryState, GameState};
+use crate::core::time::GameDate;
 
-pub type EffectFn = fn(&mut World, CountryTag, &Value) -> Result<()>;
+/// What an effect may touch: the country it runs for and the rest of the
+/// simulation state.
+pub struct EffectContext<'a> {
+    pub country: &'a str,
+    pub state: &'a mut GameState,
+    pub content: &'a ContentDb,
+    pub date: GameDate,
+    /// Focus, tech, idea or event running the effect; modifiers it adds carry this
+    pub source: ModifierSource,
+}
+
+impl EffectContext<'_> {
+    /// State of the country the effect runs for.
+    pub fn country_state(&mut self) -> Result<&mut CountryState> {
+        let tag = self.country;
+        self.state.countries.get_mut(tag).ok_or_else(|| anyhow::anyhow!("Unknown country: {}", tag))
+    }
+
+    /// Flags and variables an effect writes to: the game's when `params`
+    /// has `global: true`, otherwise the country's.
+    pub fn script(&mut self, params: &Value) -> Result<&mut ScriptState> {
+        if params["global"].as_bool().unwrap_or(false) {
+            Ok(&mut self.state.global)
+        } else {
+            Ok(&mut self.country_state()?.script)
+        }
+    }
+
+    /// Add a modifier from this effect's source.
+    pub fn add_modifier(&mut self, key: &str, value: f64, stacking: Stacking, scope: ModifierScope, expires: Option<GameDate>) {
+        let source = self.source.clone();
+        self.state.modifiers.add(Modifier { key: key.to_string(), value, stacking, source, scope, expires });
+    }
+}
+
+/// A string parameter an effect cannot run without.
+fn required_str<'v>(params: &'v Value, effect: &str, key: &str) -> Result<&'v str> {
+    params[key].as_str().with_context(|| format!("{} needs a `{}`", effect, key))
+}
+
+pub type EffectFn = fn(&mut EffectContext<'_>, &Value) -> Result<()>;
 
-#[derive(Resource, Default)]
+#[cfg_attr(feature = "bevy", derive(Resource))]
+#[derive(Default)]
 pub struct EffectRegistry {
     effects: HashMap<String, EffectFn>
 }
 
 impl EffectRegistry {
+    /// A registry holding every built-in effect.
+    pub fn core() -> Self {
+        let mut registry = EffectRegistry::default();
+
+        // Register core effects
+        registry.register("add_civ_factories", |ctx, params| {
+            let amount = params["amount"].as_f64().unwrap_or(0.0);
+            let scope = ModifierScope::Country(ctx.country.to_string());
+            ctx.add_modifier("civ_factories", amount, Stacking::Add, scope, None);
+            Ok(())
+        });
+
+        registry.register("add_mil_factories", |ctx, params| {
+            let amount = params["amount"].as_f64().unwrap_or(0.0);
+            let scope = ModifierScope::Country(ctx.country.to_string());
+            ctx.add_modifier("mil_factories", amount, Stacking::Add, scope, None);
+            Ok(())
+        });
+
+        registry.register("add_pp", |ctx, params| {
+            let amount = params["amount"].as_f64().unwrap_or(0.0);
+            ctx.country_state()?.politics.political_power += amount;
+            Ok(())
+        });
+
+        registry.register("unlock_battalion", |ctx, params| {
+            let id = required_str(params, "unlock_battalion", "battalion")?;
+            anyhow::ensure!(ctx.content.battalion(id).is_some(), "unlock_battalion: unknown battalion {}", id);
+            ctx.country_state()?.battalions.insert(id.to_string());
+            Ok(())
+        });
+
+        // Every other numeric parameter is a stat bonus, e.g. `soft_attack: 1`
+        registry.register("unit_stat_mod", |ctx, params| {
+            let unit = required_str(params, "unit_stat_mod", "unit")?;
+            let scope = ModifierScope::UnitType { country: ctx.country.to_string(), unit: unit.to_string() };
+            for (stat, value) in params.as_object().into_iter().flatten() {
+                if let Some(value) = value.as_f64() {
+                    ctx.add_modifier(stat, value, Stacking::Add, scope.clone(), None);
+                }
+            }
+            Ok(())
+        });
+
+        // General form: `{ key, value, stacking: add|multiply, unit?, province?, days? }`
+        registry.register("add_modifier", |ctx, params| {
+            let key = required_str(params, "add_modifier", "key")?;
+            let value = params["value"].as_f64().context("add_modifier needs a numeric `value`")?;
+            let stacking = match params["stacking"].as_str() {
+                None | Some("add") => Stacking::Add,
+                Some("multiply") => Stacking::Multiply,
+                Some(other) => anyhow::bail!("add_modifier: unknown stacking `{}`; expected add or multiply", other),
+            };
+            let country = ctx.country.to_string();
+            let province = match &params["province"] {
+                Value::Null => None,
+                p => Some(p.as_u64().context("add_modifier: `province` must be a province id")? as u32),
+            };
+            let scope = match (params["unit"].as_str(), province) {
+                (Some(unit), _) => ModifierScope::UnitType { country, unit: unit.to_string() },
+                (None, Some(id)) => ModifierScope::Province(id),
+                (None, None) => ModifierScope::Country(country),
+            };
+            let expires = params["days"].as_i64().map(|days| ctx.date.add_days(days));
+            ctx.add_modifier(key, value, stacking, scope, expires);
+            Ok(())
+        });
+
+        // Script state; each takes `global: true` to act on the game instead of the country
+        registry.register("set_flag", |ctx, params| {
+            let flag = required_str(params, "set_flag", "flag")?;
+            ctx.script(params)?.set_flag(flag);
+            Ok(())
+        });
+
+        registry.register("clear_flag", |ctx, params| {
+            let flag = required_str(params, "clear_flag", "flag")?;
+            ctx.script(params)?.clear_flag(flag);
+            Ok(())
+        });
+
+        registry.register("set_variable", |ctx, params| {
+            let name = required_str(params, "set_variable", "variable")?;
+            let value = params["value"].as_f64().unwrap_or(0.0);
+            ctx.script(params)?.set_variable(name, value);
+            Ok(())
+        });
+
+        registry.register("add_to_variable", |ctx, params| {
+            let name = required_str(params, "add_to_variable", "variable")?;
+            let value = params["value"].as_f64().unwrap_or(0.0);
+            ctx.script(params)?.add_to_variable(name, value);
+            Ok(())
+        });
+
+        // National spirits; `days` makes an idea temporary
+        registry.register("add_idea", |ctx, params| {
+            let id = required_str(params, "add_idea", "idea")?;
+            let def = ctx.content.idea(id).with_context(|| format!("add_idea: unknown idea {}", id))?;
+            let expires = params["days"].as_i64().map(|days| ctx.date.add_days(days));
+            ctx.state.add_idea(ctx.country, def, expires);
+            Ok(())
+        });
+
+        registry.register("remove_idea", |ctx, params| {
+            let id = required_str(params, "remove_idea", "idea")?;
+            ctx.state.remove_idea(ctx.country, id);
+            Ok(())
+        });
+
+        // Politics; `value` is a share of support, 0.05 for five points
+        registry.register("add_popularity", |ctx, params| {
+            let id = required_str(params, "add_popularity", "ideology")?;
+            let value = params["value"].as_f64().context("add_popularity needs a numeric `value`")?;
+            ctx.country_state()?.politics.add_popularity(id, value);
+            Ok(())
+        });
+
+        registry.register("set_ruling_party", |ctx, params| {
+            let id = required_str(params, "set_ruling_party", "ideology")?;
+            let content = ctx.content;
+            anyhow::ensure!(content.ideology(id).is_some(), "set_ruling_party: unknown ideology {}", id);
+            let (rules, date) = (&content.politics, ctx.date);
+            let politics = &mut ctx.country_state()?.politics;
+            if politics.ruling.as_deref() != Some(id) {
+                politics.set_ruling(id, content.holds_elections(id), rules, date);
+            }
+            Ok(())
+        });
+
+        // Characters; any country's character can join, e.g. an exile
+        registry.register("add_character", |ctx, params| {
+            let id = required_str(params, "add_character", "character")?;
+            let def = ctx.content.character(id).with_context(|| format!("add_character: unknown character {}", id))?;
+            ctx.state.add_character(ctx.country, def, ctx.content);
+            Ok(())
+        });
+
+        registry.register("remove_character", |ctx, params| {
+            let id = required_str(params, "remove_character", "character")?;
+            ctx.state.remove_character(ctx.country, id);
+            Ok(())
+        });
+
+        // Makes the character country leader, whatever their role
+        registry.register("promote_character", |ctx, params| {
+            let id = required_str(params, "promote_character", "character")?;
+            let def = ctx.content.character(id).with_context(|| format!("promote_character: unknown character {}", id))?;
+            ctx.state.appoint_leader(ctx.country, def, ctx.content);
+            Ok(())
+        });
+
+        registry
+    }
+
     pub fn register(&mut self, name: &str, effect: EffectFn) {
         self.effects.insert(name.to_string(), effect);
     }
 
-    pub fn execute(&self, world: &mut World, country: CountryTag, effect_type: &str, params: &Value) -> Result<()> {
+    pub fn contains(&self, name: &str) -> bool {
+        self.effects.contains_key(name)
+    }
+
+    pub fn execute(&self, ctx: &mut EffectContext<'_>, effect_type: &str, params: &Value) -> Result<()> {
         if let Some(effect) = self.effects.get(effect_type) {
-            effect(world, country, params)
+            effect(ctx, params)
         } else {
             Err(anyhow::anyhow!("Unknown effect type: {}", effect_type))
         }
     }
+
+    /// Run an effect as written in content, e.g. a focus or event option.
+    pub fn apply(&self, ctx: &mut EffectContext<'_>, effect: &RawEffect) -> Result<()> {
+        let params = serde_json::to_value(&effect.params)?;
+        self.execute(ctx, &effect.kind, &params)
+    }
 }
 
+#[cfg(feature = "bevy")]
 pub fn setup_effect_registry(mut commands: Commands) {
-    let mut registry = EffectRegistry::default();
-    
-            // Register core effects
-            registry.register("add_civ_factories", |_world, _country, params| {
-                let _amount = params["amount"].as_i64().unwrap_or(0);
-                // TODO: Implement effect
-                Ok(())
-            });
-
-            registry.register("add_mil_factories", |_world, _country, params| {
-                let _amount = params["amount"].as_i64().unwrap_or(0);
-                // TODO: Implement effect  
-                Ok(())
-            });
-
-            registry.register("add_pp", |_world, _country, params| {
-                let _amount = params["amount"].as_i64().unwrap_or(0);
-                // TODO: Implement effect
-                Ok(())
-            });
-
-            registry.register("unlock_battalion", |_world, _country, params| {
-                let _battalion = params["battalion"].as_str().unwrap_or("");
-                // TODO: Implement effect
-                Ok(())
-            });
-
-            registry.register("unit_stat_mod", |_world, _country, params| {
-                let _unit = params["unit"].as_str().unwrap_or("");
-                // TODO: Implement effect with stat modifications
-                Ok(())
-            });    commands.insert_resource(registry);
-}
\ No newline at end of file
+    commands.insert_resource(EffectRegistry::core());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn script_effects_write_country_or_global_state() -> Result<()> {
+        let registry = EffectRegistry::core();
+        let mut state = GameState::default();
+        state.countries.insert("GER".into(), CountryState::default());
+        let source = ModifierSource::Event("test".into());
+        let content = ContentDb::default();
+        let mut ctx = EffectContext { country: "GER", state: &mut state, content: &content, date: GameDate::default(), source };
+        let effects: Vec<RawEffect> = serde_yaml::from_str(
+            "- { type: set_flag, flag: rhineland }
+- { type: set_flag, flag: crisis, global: true }
+- { type: add_to_variable, variable: tension, value: 2 }
+- { type: add_to_variable, variable: tension, value: 0.5 }
+",
+        )?;
+        for e in &effects {
+            registry.apply(&mut ctx, e)?;
+        }
+        assert!(registry.apply(&mut ctx, &serde_yaml::from_str("{ type: set_flag }")?).is_err());
+
+        let ger = &state.countries["GER"].script;
+        assert!(ger.has_flag("rhineland") && !ger.has_flag("crisis"));
+        assert_eq!(ger.variable("tension"), 2.5);
+        assert!(state.global.has_flag("crisis"));
+        Ok(())
+    }
+}
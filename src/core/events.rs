@@ -0,0 +1,217 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::core::focus::RawEffect;
+use crate::core::script::ScriptState;
+use crate::core::sim::CountryState;
+use crate::core::time::GameDate;
+
+/// A scripted news event from `game/events/*.yaml`.
+///
+/// Events are checked once a day for every country. One with a `date` fires
+/// on the first day on or after it that its trigger holds; one with a
+/// `mean_time_to_happen` fires with a daily chance of one in that many days
+/// while its trigger holds.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EventDef {
+    /// Unique event identifier, e.g. `ger.remilitarisation`
+    pub id: String,
+    /// Headline shown to the player
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    /// Condition the country must meet; no trigger means always
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "serde_yaml::with::singleton_map_recursive")]
+    #[schemars(with = "Option<Trigger>")]
+    pub trigger: Option<Trigger>,
+    /// Earliest date the event can fire
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<GameDate>,
+    /// Average number of days until the event fires once its trigger holds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(range(min = 1))]
+    pub mean_time_to_happen: Option<u32>,
+    /// Whether the event can fire again for a country it already fired for
+    #[serde(default)]
+    pub repeatable: bool,
+    /// Choices offered when the event fires
+    #[schemars(length(min = 1))]
+    pub options: Vec<EventOption>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EventOption {
+    /// Button text of the option
+    pub name: String,
+    /// Relative chance of the AI picking this option
+    #[serde(default = "default_ai_weight")]
+    pub ai_weight: u32,
+    #[serde(default)]
+    pub effects: Vec<RawEffect>,
+}
+
+fn default_ai_weight() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+pub struct EventFile { pub events: Vec<EventDef> }
+
+/// Condition on a country, written as a single-key map, e.g.
+/// `{ has_completed_focus: rhineland }` or `{ all: [...] }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Trigger {
+    /// The country has this tag
+    Tag(String),
+    HasCompletedFocus(String),
+    HasTech(String),
+    /// The country has this national spirit
+    HasIdea(String),
+    /// The country is ruled by this ideology
+    RulingIdeology(String),
+    /// An ideology has at least `value` of the country's support, 0 to 1
+    PopularityAtLeast(PopularityCheck),
+    /// Whether the country is in a civil war
+    InCivilWar(bool),
+    /// The country has this flag, set with the `set_flag` effect
+    HasFlag(String),
+    /// The game has this flag, set with `set_flag` and `global: true`
+    HasGlobalFlag(String),
+    /// A country variable is at least `value`; unset variables are 0
+    VariableAtLeast(VariableCheck),
+    /// A game-wide variable is at least `value`
+    GlobalVariableAtLeast(VariableCheck),
+    /// The current date is on or after this one
+    DateAfter(GameDate),
+    /// The current date is before this one
+    DateBefore(GameDate),
+    All(Vec<Trigger>),
+    Any(Vec<Trigger>),
+    Not(Box<Trigger>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PopularityCheck {
+    pub ideology: String,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct VariableCheck {
+    pub variable: String,
+    pub value: f64,
+}
+
+/// What a trigger is evaluated against.
+#[derive(Debug, Clone, Copy)]
+pub struct Scope<'a> {
+    pub tag: &'a str,
+    pub country: &'a CountryState,
+    pub global: &'a ScriptState,
+    pub date: GameDate,
+}
+
+impl Trigger {
+    pub fn holds(&self, scope: &Scope<'_>) -> bool {
+        match self {
+            Trigger::Tag(tag) => scope.tag == tag,
+            Trigger::HasCompletedFocus(id) => scope.country.focus.completed.contains(id),
+            Trigger::HasTech(id) => scope.country.research.completed.contains(id),
+            Trigger::HasIdea(id) => scope.country.has_idea(id),
+            Trigger::RulingIdeology(id) => scope.country.politics.ruling.as_deref() == Some(id),
+            Trigger::PopularityAtLeast(c) => scope.country.politics.popularity(&c.ideology) >= c.value,
+            Trigger::InCivilWar(at_war) => scope.country.politics.civil_war.is_some() == *at_war,
+            Trigger::HasFlag(flag) => scope.country.script.has_flag(flag),
+            Trigger::HasGlobalFlag(flag) => scope.global.has_flag(flag),
+            Trigger::VariableAtLeast(c) => scope.country.script.variable(&c.variable) >= c.value,
+            Trigger::GlobalVariableAtLeast(c) => scope.global.variable(&c.variable) >= c.value,
+            Trigger::DateAfter(date) => scope.date >= *date,
+            Trigger::DateBefore(date) => scope.date < *date,
+            Trigger::All(all) => all.iter().all(|t| t.holds(scope)),
+            Trigger::Any(any) => any.iter().any(|t| t.holds(scope)),
+            Trigger::Not(t) => !t.holds(scope),
+        }
+    }
+
+    /// Every trigger nested in this one, this one included.
+    pub fn walk(&self) -> Vec<&Trigger> {
+        let mut out = vec![self];
+        match self {
+            Trigger::All(ts) | Trigger::Any(ts) => ts.iter().for_each(|t| out.extend(t.walk())),
+            Trigger::Not(t) => out.extend(t.walk()),
+            _ => {}
+        }
+        out
+    }
+}
+
+impl EventDef {
+    /// Whether the event may fire today for the country in `scope`, leaving
+    /// out the mean-time-to-happen roll.
+    pub fn eligible(&self, scope: &Scope<'_>) -> bool {
+        if !self.repeatable && scope.country.events.fired.contains(&self.id) {
+            return false;
+        }
+        if scope.country.events.pending.iter().any(|p| p.event == self.id) {
+            return false;
+        }
+        if self.date.is_some_and(|d| scope.date < d) {
+            return false;
+        }
+        self.trigger.as_ref().is_none_or(|t| t.holds(scope))
+    }
+}
+
+/// An event that fired and is waiting for its country to pick an option.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingEvent {
+    pub event: String,
+    pub fired: GameDate,
+}
+
+/// Per-country event history.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CountryEventState {
+    /// Events that fired at least once
+    pub fired: BTreeSet<String>,
+    /// Fired events still waiting for a choice, oldest first
+    pub pending: Vec<PendingEvent>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn triggers_nest_and_fired_events_are_not_eligible_again() {
+        let event: EventDef = serde_yaml::from_str(
+            "id: e
+title: E
+date: 1936-03-07
+trigger: { all: [{ tag: GER }, { not: { has_tech: radar } }, { any: [{ has_completed_focus: a }, { date_after: 1937-01-01 }] }] }
+options: [{ name: OK }]
+",
+        )
+        .unwrap();
+        let mut country = CountryState::default();
+        country.focus.completed.insert("a".into());
+        let on = |date: &str| -> GameDate { date.parse().unwrap() };
+
+        let global = ScriptState::default();
+        let eligible = |country: &CountryState, tag: &'static str, date: GameDate| {
+            event.eligible(&Scope { tag, country, global: &global, date })
+        };
+        assert!(!eligible(&country, "GER", on("1936-03-06")));
+        assert!(eligible(&country, "GER", on("1936-03-07")));
+        assert!(!eligible(&country, "ITA", on("1936-03-07")));
+        assert_eq!(event.options[0].ai_weight, 1);
+
+        country.events.fired.insert("e".into());
+        assert!(!eligible(&country, "GER", on("1936-03-07")));
+        country.events.fired.clear();
+        country.research.completed.insert("radar".into());
+        assert!(!eligible(&country, "GER", on("1936-03-07")));
+    }
+}
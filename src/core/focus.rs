@@ -1,27 +1,36 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Focus {
+    /// Unique focus identifier
     pub id: String,
+    /// Display name of the focus
     pub name: String,
+    /// Number of days to complete
+    #[schemars(range(min = 1))]
     pub days: u32,
+    /// List of focus IDs that must be completed first
     #[serde(default)]
     pub prerequisites: Vec<String>,
+    /// List of focus IDs that cannot be taken if this one is completed
     #[serde(default)]
     pub mutually_exclusive: Vec<String>,
     #[serde(default)]
     pub effects: Vec<RawEffect>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RawEffect {
+    /// Effect name, looked up in the effect registry
     #[serde(rename = "type")]
     pub kind: String,
     #[serde(flatten)]
+    #[schemars(with = "serde_json::Map<String, serde_json::Value>")]
     pub params: serde_yaml::Value,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 pub struct FocusTree { pub focuses: Vec<Focus> }
 
 impl FocusTree {
@@ -0,0 +1,43 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::core::modifiers::Stacking;
+use crate::core::time::GameDate;
+
+/// A national spirit from `game/ideas/*.yaml`: a bundle of modifiers a
+/// country keeps until an effect removes it or it runs out.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct IdeaDef {
+    /// Unique idea identifier
+    pub id: String,
+    /// Display name of the idea
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub modifiers: Vec<IdeaModifier>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct IdeaModifier {
+    /// Modifier key, e.g. `research_speed` or a battalion stat like `soft_attack`
+    pub key: String,
+    pub value: f64,
+    #[serde(default)]
+    pub stacking: Stacking,
+    /// Limit the modifier to one battalion type instead of the whole country
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+pub struct IdeaFile { pub ideas: Vec<IdeaDef> }
+
+/// An idea a country currently has.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActiveIdea {
+    pub id: String,
+    /// Removed at the start of this date; `None` keeps it until `remove_idea`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<GameDate>,
+}
@@ -5,11 +5,21 @@ pub mod province;
 pub mod adjacency;
 pub mod focus;
 pub mod units;
+pub mod research;
+pub mod scenario;
 pub mod save;
+pub mod script;
+pub mod sim;
 pub mod effects;
+pub mod events;
+pub mod ideas;
+pub mod modifiers;
+pub mod politics;
+pub mod characters;
 pub mod simulation;
 pub mod components;
 pub mod types;
+mod mod_tests;
 
 pub use time::*;
 pub use data::*;
@@ -18,8 +28,17 @@ pub use province::*;
 pub use adjacency::*;
 pub use focus::*;
 pub use units::*;
+pub use research::*;
+pub use scenario::*;
 pub use save::*;
+pub use script::*;
+pub use sim::*;
 pub use effects::*;
+pub use events::*;
+pub use ideas::*;
+pub use modifiers::*;
+pub use politics::*;
+pub use characters::*;
 pub use simulation::*;
 pub use components::*;
 pub use types::*;
@@ -1,13 +1,24 @@
 #[cfg(test)]
 mod tests {
-    use super::super::core::adjacency::Adjacency;
-    use super::super::core::province::ProvinceDef;
+    use std::path::PathBuf;
+
+    use crate::content::db::ContentDb;
+    use crate::content::diagnostics::codes;
+    use crate::content::validator::structural_diagnostics;
+    use crate::core::adjacency::Adjacency;
+    use crate::core::data::{Edge, Pos};
+    use crate::core::province::{ProvinceDef, ProvincesList};
 
     #[test]
-    fn adjacency_refs_exist() {
-        let provinces = vec![ProvinceDef { id: 0, name: "A".into(), pos: crate::core::data::Pos { x: 0, y: 0 } }];
-        let adj = Adjacency { edges: vec![crate::core::data::Edge { a: 0, b: 0 }] };
-        assert_eq!(provinces.len(), 1);
-        assert_eq!(adj.edges.len(), 1);
+    fn self_loop_edges_are_reported() {
+        let provinces = vec![ProvinceDef { id: 0, name: "A".into(), pos: Pos { x: 0, y: 0 }, island: false, unowned: true }];
+        let adj = Adjacency { edges: vec![Edge { a: 0, b: 0 }] };
+        let files = vec![
+            (PathBuf::from("game/map/provinces.yaml"), serde_yaml::to_string(&ProvincesList { provinces }).unwrap()),
+            (PathBuf::from("game/map/adjacency.yaml"), serde_yaml::to_string(&adj).unwrap()),
+        ];
+        let diags = structural_diagnostics(&ContentDb::from_files(&files));
+        assert!(diags.iter().any(|d| d.code == codes::EDGE_SELF_LOOP), "{:?}", diags);
+        assert!(!diags.iter().any(|d| d.code == codes::EDGE_UNKNOWN_PROVINCE));
     }
 }
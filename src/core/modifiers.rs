@@ -0,0 +1,228 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::core::time::GameDate;
+use crate::core::units::BattalionStats;
+
+/// Country-wide keys the simulation reads. Battalion stat names
+/// ([`BattalionStats::NAMES`]) are modifier keys too.
+pub const COUNTRY_KEYS: [&str; 5] = ["civ_factories", "mil_factories", "manpower", "research_speed", "political_power_gain"];
+
+/// Country keys the simulation evaluates from zero rather than from a
+/// `resources` base, so only additive modifiers change them. Ideology
+/// drift keys behave the same way.
+pub const ZERO_BASE_KEYS: [&str; 1] = ["research_speed"];
+
+/// Whether anything reads modifiers with this key.
+pub fn is_known_key(key: &str) -> bool {
+    COUNTRY_KEYS.contains(&key) || BattalionStats::NAMES.contains(&key)
+}
+
+/// What granted a modifier, for breakdowns and for removing it again.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "id", rename_all = "snake_case")]
+pub enum ModifierSource {
+    Focus(String),
+    Tech(String),
+    Idea(String),
+    Event(String),
+    Character(String),
+}
+
+impl fmt::Display for ModifierSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModifierSource::Focus(id) => write!(f, "focus {}", id),
+            ModifierSource::Tech(id) => write!(f, "tech {}", id),
+            ModifierSource::Idea(id) => write!(f, "idea {}", id),
+            ModifierSource::Event(id) => write!(f, "event {}", id),
+            ModifierSource::Character(id) => write!(f, "character {}", id),
+        }
+    }
+}
+
+/// What a modifier applies to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModifierScope {
+    Country(String),
+    Province(u32),
+    /// One battalion type of one country
+    UnitType { country: String, unit: String },
+    /// Divisions a general commands
+    General { country: String, general: String },
+}
+
+impl ModifierScope {
+    /// Country the scope belongs to; provinces have none of their own.
+    pub fn country(&self) -> Option<&str> {
+        match self {
+            ModifierScope::Country(tag)
+            | ModifierScope::UnitType { country: tag, .. }
+            | ModifierScope::General { country: tag, .. } => Some(tag),
+            ModifierScope::Province(_) => None,
+        }
+    }
+}
+
+/// How a modifier combines with the others on the same key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Stacking {
+    /// Added to the base value
+    #[default]
+    Add,
+    /// A fraction, `0.1` for +10%. Multiplicative modifiers are summed and
+    /// applied once, so two +10% make +20% rather than +21%.
+    Multiply,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Modifier {
+    /// Name of the value it changes, e.g. `research_speed` or `soft_attack`
+    pub key: String,
+    pub value: f64,
+    #[serde(default)]
+    pub stacking: Stacking,
+    pub source: ModifierSource,
+    pub scope: ModifierScope,
+    /// Removed at the start of this date; `None` lasts until its source removes it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<GameDate>,
+}
+
+/// Every active modifier in the game.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Modifiers {
+    list: Vec<Modifier>,
+}
+
+impl Modifiers {
+    pub fn add(&mut self, modifier: Modifier) {
+        self.list.push(modifier);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Modifier> {
+        self.list.iter()
+    }
+
+    /// Drop everything `source` granted, e.g. when an idea is removed.
+    /// Returns how many modifiers went.
+    pub fn remove_source(&mut self, source: &ModifierSource) -> usize {
+        let before = self.list.len();
+        self.list.retain(|m| &m.source != source);
+        before - self.list.len()
+    }
+
+    /// Drop what `source` granted to `scope` alone.
+    pub fn remove_scoped(&mut self, source: &ModifierSource, scope: &ModifierScope) -> usize {
+        let before = self.list.len();
+        self.list.retain(|m| !(&m.source == source && &m.scope == scope));
+        before - self.list.len()
+    }
+
+    /// Like [`Modifiers::remove_source`], limited to modifiers on `country`
+    /// or its unit types.
+    pub fn remove_source_for(&mut self, country: &str, source: &ModifierSource) -> usize {
+        let before = self.list.len();
+        self.list.retain(|m| !(&m.source == source && m.scope.country() == Some(country)));
+        before - self.list.len()
+    }
+
+    /// Drop modifiers that expire on or before `date` and return them.
+    pub fn expire(&mut self, date: GameDate) -> Vec<Modifier> {
+        let (expired, kept) = self.list.drain(..).partition(|m| m.expires.is_some_and(|e| e <= date));
+        self.list = kept;
+        expired
+    }
+
+    /// `key` for something covered by any of `scopes`, starting from `base`:
+    /// `(base + additive) * (1 + multiplicative)`.
+    pub fn breakdown(&self, key: &str, base: f64, scopes: &[ModifierScope]) -> Breakdown {
+        let terms: Vec<Modifier> = self
+            .list
+            .iter()
+            .filter(|m| m.key == key && scopes.contains(&m.scope))
+            .cloned()
+            .collect();
+        let sum = |stacking| terms.iter().filter(|m| m.stacking == stacking).map(|m| m.value).sum::<f64>();
+        let value = (base + sum(Stacking::Add)) * (1.0 + sum(Stacking::Multiply));
+        Breakdown { key: key.to_string(), base, terms, value }
+    }
+
+    pub fn value(&self, key: &str, base: f64, scopes: &[ModifierScope]) -> f64 {
+        self.breakdown(key, base, scopes).value
+    }
+}
+
+/// An effective value and the modifiers that produced it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Breakdown {
+    pub key: String,
+    pub base: f64,
+    /// Contributing modifiers, oldest first
+    pub terms: Vec<Modifier>,
+    pub value: f64,
+}
+
+impl fmt::Display for Breakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: {}", self.key, round(self.value))?;
+        writeln!(f, "  base {}", round(self.base))?;
+        for m in &self.terms {
+            match m.stacking {
+                Stacking::Add => write!(f, "  {:+} from {}", round(m.value), m.source)?,
+                Stacking::Multiply => write!(f, "  {:+}% from {}", round(m.value * 100.0), m.source)?,
+            }
+            match m.expires {
+                Some(date) => writeln!(f, " until {}", date)?,
+                None => writeln!(f)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Three decimals are plenty for display and hide float noise like 7.200000000000001.
+fn round(v: f64) -> f64 {
+    (v * 1000.0).round() / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modifiers_stack_expire_and_explain_themselves() {
+        let ger = ModifierScope::Country("GER".into());
+        let infantry = ModifierScope::UnitType { country: "GER".into(), unit: "infantry".into() };
+        let modifier = |value, stacking, source, scope: &ModifierScope, expires: Option<&str>| Modifier {
+            key: "soft_attack".into(),
+            value,
+            stacking,
+            source,
+            scope: scope.clone(),
+            expires: expires.map(|d| d.parse().unwrap()),
+        };
+        let mut mods = Modifiers::default();
+        mods.add(modifier(1.0, Stacking::Add, ModifierSource::Tech("weapons".into()), &infantry, None));
+        mods.add(modifier(0.1, Stacking::Multiply, ModifierSource::Focus("army".into()), &ger, None));
+        mods.add(modifier(0.1, Stacking::Multiply, ModifierSource::Event("rally".into()), &ger, Some("1936-02-01")));
+        mods.add(modifier(5.0, Stacking::Add, ModifierSource::Idea("other".into()), &ModifierScope::Country("ITA".into()), None));
+
+        let scopes = [ger.clone(), infantry.clone()];
+        let b = mods.breakdown("soft_attack", 5.0, &scopes);
+        assert!((b.value - 7.2).abs() < 1e-9);
+        assert_eq!(b.terms.len(), 3);
+        assert_eq!(
+            b.to_string(),
+            "soft_attack: 7.2\n  base 5\n  +1 from tech weapons\n  +10% from focus army\n  +10% from event rally until 1936-02-01\n"
+        );
+        assert_eq!(mods.value("soft_attack", 5.0, &[infantry]), 6.0);
+
+        assert_eq!(mods.expire("1936-02-01".parse().unwrap()).len(), 1);
+        assert_eq!(mods.remove_source(&ModifierSource::Tech("weapons".into())), 1);
+        assert!((mods.value("soft_attack", 5.0, &scopes) - 5.5).abs() < 1e-9);
+    }
+}
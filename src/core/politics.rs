@@ -0,0 +1,265 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::core::country::{Color, CountryDef};
+use crate::core::time::GameDate;
+
+/// An ideology from `game/ideologies.yaml`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct IdeologyDef {
+    /// Unique ideology identifier, e.g. `fascism`
+    pub id: String,
+    /// Display name of the ideology
+    pub name: String,
+    pub color: Option<Color>,
+    /// Whether governments of this ideology hold elections
+    #[serde(default)]
+    pub elections: bool,
+}
+
+/// `game/ideologies.yaml`: the ideologies and the thresholds that topple governments.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+pub struct IdeologyFile {
+    pub ideologies: Vec<IdeologyDef>,
+    #[serde(default)]
+    pub rules: PoliticsRules,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PoliticsRules {
+    /// Days between elections while the ruling ideology holds them
+    #[serde(default = "default_election_interval")]
+    #[schemars(range(min = 1))]
+    pub election_interval_days: u32,
+    /// Popularity at which an opposition ideology takes power in a coup.
+    /// Only countries without elections have coups.
+    #[serde(default = "default_coup_threshold")]
+    #[schemars(range(min = 0.0, max = 1.0))]
+    pub coup_threshold: f64,
+    /// Popularity at which an opposition ideology rises in civil war, in a
+    /// country without elections. The war ends when it falls below this again.
+    #[serde(default = "default_civil_war_threshold")]
+    #[schemars(range(min = 0.0, max = 1.0))]
+    pub civil_war_threshold: f64,
+}
+
+fn default_election_interval() -> u32 {
+    1460
+}
+
+fn default_coup_threshold() -> f64 {
+    0.6
+}
+
+fn default_civil_war_threshold() -> f64 {
+    0.4
+}
+
+impl Default for PoliticsRules {
+    fn default() -> Self {
+        PoliticsRules {
+            election_interval_days: default_election_interval(),
+            coup_threshold: default_coup_threshold(),
+            civil_war_threshold: default_civil_war_threshold(),
+        }
+    }
+}
+
+/// Modifier key whose value is added to `ideology`'s popularity every day.
+pub fn drift_key(ideology: &str) -> String {
+    format!("{}_drift", ideology)
+}
+
+/// Why a country's ruling ideology changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GovernmentChange {
+    Election,
+    Coup,
+    CivilWar,
+    /// The `set_ruling_party` effect
+    Effect,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CivilWar {
+    /// Ideology of the rebels
+    pub ideology: String,
+    pub started: GameDate,
+}
+
+/// A country's government and party popularity.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CountryPolitics {
+    /// Ruling ideology; countries without one take no part in politics
+    pub ruling: Option<String>,
+    /// Share of support per ideology, summing to 1
+    pub popularity: BTreeMap<String, f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_election: Option<GameDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub civil_war: Option<CivilWar>,
+    /// Spent on advisors; gained daily through `political_power_gain`
+    #[serde(default)]
+    pub political_power: f64,
+}
+
+impl CountryPolitics {
+    /// Politics at the scenario start. Popularity in content is relative, so
+    /// `{ fascism: 60, democratic: 40 }` works as well as fractions; without
+    /// any the ruling ideology has all the support.
+    pub fn starting(def: &CountryDef, rules: &PoliticsRules, holds_elections: bool, start: GameDate) -> Self {
+        let mut popularity = def.popularity.clone().unwrap_or_default();
+        if popularity.is_empty() {
+            popularity.extend(def.ideology.iter().map(|i| (i.clone(), 1.0)));
+        }
+        let political_power = def.resources.as_ref().and_then(|r| r.get("political_power")?.as_f64()).unwrap_or(0.0);
+        let mut politics = CountryPolitics { ruling: def.ideology.clone(), popularity, political_power, ..Default::default() };
+        politics.normalize();
+        if holds_elections {
+            politics.next_election = Some(start.add_days(i64::from(rules.election_interval_days)));
+        }
+        politics
+    }
+
+    /// Hand power to `ideology`. Ends any civil war, and schedules the next
+    /// election `rules.election_interval_days` after `date` if the new
+    /// government holds them.
+    pub fn set_ruling(&mut self, ideology: &str, holds_elections: bool, rules: &PoliticsRules, date: GameDate) {
+        self.ruling = Some(ideology.to_string());
+        self.civil_war = None;
+        self.next_election = holds_elections.then(|| date.add_days(i64::from(rules.election_interval_days)));
+    }
+
+    /// Support for `ideology`, 0 to 1.
+    pub fn popularity(&self, ideology: &str) -> f64 {
+        self.popularity.get(ideology).copied().unwrap_or(0.0)
+    }
+
+    /// Move `ideology`'s support by `amount`, clamped to 0..1. The others
+    /// make up the difference in proportion to their own support, or evenly
+    /// when none of them has any. An ideology with every other one missing
+    /// from `popularity` keeps all the support.
+    pub fn add_popularity(&mut self, ideology: &str, amount: f64) {
+        let current = self.popularity(ideology);
+        let others = 1.0 - current;
+        let target = (current + amount).clamp(0.0, 1.0);
+        if others <= 0.0 {
+            let rivals = self.popularity.keys().filter(|id| *id != ideology).count();
+            if target >= current || rivals == 0 {
+                return;
+            }
+            let share = (current - target) / rivals as f64;
+            for (id, v) in self.popularity.iter_mut() {
+                if id != ideology {
+                    *v = share;
+                }
+            }
+        } else {
+            for (id, v) in self.popularity.iter_mut() {
+                if id != ideology {
+                    *v *= (1.0 - target) / others;
+                }
+            }
+        }
+        self.popularity.insert(ideology.to_string(), target);
+    }
+
+    /// Clamp negative support to zero and scale the rest to sum to 1.
+    pub fn normalize(&mut self) {
+        for v in self.popularity.values_mut() {
+            *v = v.max(0.0);
+        }
+        let total: f64 = self.popularity.values().sum();
+        if total > 0.0 {
+            for v in self.popularity.values_mut() {
+                *v /= total;
+            }
+        }
+    }
+
+    /// The most popular ideology; ties go to the first by id.
+    pub fn most_popular(&self) -> Option<(&str, f64)> {
+        strongest(self.popularity.iter())
+    }
+
+    /// The most popular ideology other than the ruling one.
+    pub fn strongest_opposition(&self) -> Option<(&str, f64)> {
+        let ruling = self.ruling.as_deref();
+        strongest(self.popularity.iter().filter(|(id, _)| Some(id.as_str()) != ruling))
+    }
+}
+
+fn strongest<'a>(ideologies: impl Iterator<Item = (&'a String, &'a f64)>) -> Option<(&'a str, f64)> {
+    ideologies.fold(None, |best, (id, &v)| match best {
+        Some((_, b)) if b >= v => best,
+        _ => Some((id.as_str(), v)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn popularity_normalizes_and_ranks_the_opposition() {
+        let def: CountryDef =
+            serde_yaml::from_str("tag: GER\nname: Germany\nideology: fascism\npopularity: { fascism: 60, democratic: 30, communism: 10 }\n")
+                .unwrap();
+        let start: GameDate = "1936-01-01".parse().unwrap();
+        let mut p = CountryPolitics::starting(&def, &PoliticsRules::default(), false, start);
+        assert!((p.popularity("fascism") - 0.6).abs() < 1e-9);
+        assert_eq!(p.next_election, None);
+        assert_eq!(p.strongest_opposition(), Some(("democratic", p.popularity("democratic"))));
+
+        // Fascism and democratic give up the 0.4 between them, keeping their 2:1 ratio
+        p.add_popularity("communism", 0.4);
+        assert!((p.popularity("communism") - 0.5).abs() < 1e-9);
+        assert!((p.popularity("fascism") - 2.0 * p.popularity("democratic")).abs() < 1e-9);
+        assert!((p.popularity.values().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert_eq!(p.strongest_opposition().map(|(id, _)| id), Some("communism"));
+        assert_eq!(p.most_popular().map(|(id, _)| id), Some("communism"));
+
+        p.add_popularity("fascism", -5.0);
+        assert_eq!(p.popularity("fascism"), 0.0);
+        assert!((p.popularity.values().sum::<f64>() - 1.0).abs() < 1e-9);
+
+        let elected = CountryPolitics::starting(&def, &PoliticsRules::default(), true, start);
+        assert_eq!(elected.next_election.unwrap().to_string(), "1939-12-31");
+    }
+
+    #[test]
+    fn drifting_past_full_support_stays_finite() {
+        let def: CountryDef =
+            serde_yaml::from_str("tag: GER\nname: Germany\nideology: fascism\npopularity: { fascism: 90, democratic: 10 }\n").unwrap();
+        let mut p = CountryPolitics::starting(&def, &PoliticsRules::default(), false, "1936-01-01".parse().unwrap());
+        for _ in 0..1000 {
+            p.add_popularity("fascism", 0.01);
+        }
+        assert_eq!(p.popularity("fascism"), 1.0);
+        assert_eq!(p.popularity("democratic"), 0.0);
+        assert!(p.popularity.values().all(|v| v.is_finite()));
+
+        // Rivals can still win support back from nothing
+        p.add_popularity("democratic", 0.25);
+        assert!((p.popularity("democratic") - 0.25).abs() < 1e-9);
+        assert!((p.popularity.values().sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn losing_full_support_shares_it_among_the_rest() {
+        let def: CountryDef = serde_yaml::from_str("tag: GER\nname: Germany\nideology: fascism\n").unwrap();
+        let mut p = CountryPolitics::starting(&def, &PoliticsRules::default(), false, "1936-01-01".parse().unwrap());
+        assert_eq!(p.popularity("fascism"), 1.0);
+        // Nobody else is listed, so there is no one to hand support to
+        p.add_popularity("fascism", -0.2);
+        assert_eq!(p.popularity("fascism"), 1.0);
+
+        p.popularity.extend([("communism".to_string(), 0.0), ("democratic".to_string(), 0.0)]);
+        p.add_popularity("fascism", -0.2);
+        assert!((p.popularity("fascism") - 0.8).abs() < 1e-9);
+        assert!((p.popularity("communism") - 0.1).abs() < 1e-9);
+        assert!((p.popularity("democratic") - 0.1).abs() < 1e-9);
+    }
+}
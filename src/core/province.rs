@@ -1,13 +1,25 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ProvinceDef {
+    /// Unique province identifier
     pub id: u32,
+    /// Display name of the province
     pub name: String,
+    /// Position on the map
     pub pos: crate::core::data::Pos,
+    /// Deliberately cut off from the rest of the map; silences the
+    /// validator's island and missing-edge warnings
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub island: bool,
+    /// Belongs to no country at game start; otherwise exactly one
+    /// country must list it in `owned_provinces`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub unowned: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ProvincesList {
     pub provinces: Vec<ProvinceDef>,
 }
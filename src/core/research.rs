@@ -0,0 +1,34 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::core::focus::RawEffect;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TechDef {
+    /// Unique technology identifier
+    pub id: String,
+    /// Display name of the technology
+    pub name: String,
+    /// Number of days to research
+    #[schemars(range(min = 1))]
+    pub days: u32,
+    /// List of technology IDs that must be researched first
+    #[serde(default)]
+    pub prerequisites: Vec<String>,
+    #[serde(default)]
+    pub effects: Vec<RawEffect>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+pub struct Technologies { pub technologies: Vec<TechDef> }
+
+/// Per-country research setup from `game/countries/<TAG>/research.yaml`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+pub struct CountryResearch {
+    /// Number of technologies researched in parallel
+    #[schemars(range(min = 1, max = 5))]
+    pub research_slots: u32,
+    /// Technology IDs to research, in order
+    #[serde(default)]
+    pub research_queue: Vec<String>,
+}
@@ -1,20 +1,131 @@
 use serde::{Deserialize, Serialize};
-use crate::core::time::Clock;
+use std::collections::{BTreeMap, BTreeSet};
+use crate::core::time::{Clock, GameDate};
 use crate::core::province::ProvinceDef;
+use crate::core::characters::CountryCharacters;
+use crate::core::events::CountryEventState;
+use crate::core::ideas::ActiveIdea;
+use crate::core::modifiers::{Modifier, ModifierSource};
+use crate::core::politics::CountryPolitics;
+use crate::core::script::ScriptSave;
+use crate::core::sim::{CountryFocusState, CountryResearchState, DivisionState, InputLog, LoggedInput, Simulation};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameSave {
     pub version: String,
     pub day: u32,
+    /// In-game date at save time; older saves without it load as the default start
+    #[serde(default)]
+    pub date: GameDate,
     pub provinces: Vec<ProvinceDef>,
+    /// Script flags and variables; see [`GameState::script_save`](crate::core::sim::GameState::script_save)
+    #[serde(default)]
+    pub script: ScriptSave,
+    /// National spirits per country; see [`GameState::ideas_save`](crate::core::sim::GameState::ideas_save)
+    #[serde(default)]
+    pub ideas: BTreeMap<String, Vec<ActiveIdea>>,
+    /// Governments and popularity; see [`GameState::politics_save`](crate::core::sim::GameState::politics_save)
+    #[serde(default)]
+    pub politics: BTreeMap<String, CountryPolitics>,
+    /// Rosters, leaders, advisors and commands; see [`GameState::characters_save`](crate::core::sim::GameState::characters_save)
+    #[serde(default)]
+    pub characters: BTreeMap<String, CountryCharacters>,
+    /// Focus, research and event progress per country
+    #[serde(default)]
+    pub progress: BTreeMap<String, CountryProgress>,
+    /// Division positions and movement, by id
+    #[serde(default)]
+    pub divisions: BTreeMap<u64, DivisionState>,
+    /// Modifiers granted by focuses, techs and events. Ideas and characters
+    /// re-create theirs from content on load.
+    #[serde(default)]
+    pub modifiers: Vec<Modifier>,
+    #[serde(default)]
+    pub seed: u64,
+    /// See [`SimRng::position`](crate::core::sim::SimRng::position)
+    #[serde(default)]
+    pub rng_position: u128,
+    #[serde(default)]
+    pub players: BTreeSet<String>,
+    /// Inputs submitted but not yet applied
+    #[serde(default)]
+    pub inputs: Vec<LoggedInput>,
+    /// Seed and every input since the scenario start, for replaying the game
+    #[serde(default)]
+    pub log: Option<InputLog>,
+}
+
+/// What a country has started and finished.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CountryProgress {
+    pub focus: CountryFocusState,
+    pub research: CountryResearchState,
+    pub events: CountryEventState,
+    /// Battalion types unlocked by effects
+    #[serde(default)]
+    pub battalions: BTreeSet<String>,
 }
 
 impl GameSave {
+    /// A save of the map alone, for tools without a running simulation.
     pub fn from_world(clock: &Clock, provinces: &[ProvinceDef]) -> Self {
         GameSave {
             version: "0.1".to_string(),
             day: clock.current_day,
+            date: clock.date(),
             provinces: provinces.to_vec(),
+            script: ScriptSave::default(),
+            ideas: BTreeMap::new(),
+            politics: BTreeMap::new(),
+            characters: BTreeMap::new(),
+            progress: BTreeMap::new(),
+            divisions: BTreeMap::new(),
+            modifiers: Vec::new(),
+            seed: 0,
+            rng_position: 0,
+            players: BTreeSet::new(),
+            inputs: Vec::new(),
+            log: None,
+        }
+    }
+
+    /// Everything [`Simulation::load_save`] needs to carry on where `sim` is.
+    pub fn from_sim(sim: &Simulation) -> Self {
+        let provinces: Vec<ProvinceDef> = sim.content().provinces.values().cloned().collect();
+        let state = &sim.state;
+        let progress = state
+            .countries
+            .iter()
+            .map(|(tag, c)| {
+                let progress = CountryProgress {
+                    focus: c.focus.clone(),
+                    research: c.research.clone(),
+                    events: c.events.clone(),
+                    battalions: c.battalions.clone(),
+                };
+                (tag.clone(), progress)
+            })
+            .collect();
+        let modifiers = state
+            .modifiers
+            .iter()
+            .filter(|m| !matches!(m.source, ModifierSource::Idea(_) | ModifierSource::Character(_)))
+            .cloned()
+            .collect();
+        GameSave {
+            script: state.script_save(),
+            ideas: state.ideas_save(),
+            politics: state.politics_save(),
+            characters: state.characters_save(),
+            progress,
+            divisions: state.divisions.clone(),
+            modifiers,
+            seed: sim.rng.seed(),
+            rng_position: sim.rng.position(),
+            players: state.players.clone(),
+            inputs: sim.pending_inputs().cloned().collect(),
+            log: Some(sim.input_log().clone()),
+            ..GameSave::from_world(&sim.clock, &provinces)
         }
     }
 }
@@ -35,22 +146,94 @@ pub fn load_from_path(path: &std::path::Path) -> anyhow::Result<GameSave> {
 mod tests {
     use super::*;
     use tempfile::tempdir;
-    use crate::core::time::Clock;
-    use crate::core::data::Pos;
-    use crate::core::province::ProvinceDef;
+    use crate::content::db::ContentDb;
+    use crate::core::sim::{Moving, SimInput, StopWhen};
 
     #[test]
-    fn save_and_load_roundtrip() {
-        let clock = Clock { current_day: 42, paused: false, speed_idx: 0, acc: 0.0 };
-        let provinces = vec![ProvinceDef { id: 7, name: "Test".into(), pos: Pos { x: 10, y: 20 } }];
-        let save = GameSave::from_world(&clock, &provinces);
+    fn a_saved_simulation_carries_on_after_loading() {
+        let file = |path: &str, yaml: &str| (std::path::PathBuf::from(path), yaml.to_string());
+        let files = vec![
+            file("game/ideologies.yaml", "ideologies:\n  - { id: fascism, name: F }\n  - { id: communism, name: C }\n"),
+            file("game/countries/GER/country.yaml", "tag: GER\nname: Germany\nideology: fascism\npopularity: { fascism: 80, communism: 20 }\n"),
+            file("game/countries/ITA/country.yaml", "tag: ITA\nname: Italy\nideology: fascism\n"),
+            file(
+                "game/countries/GER/characters.yaml",
+                "characters:\n  - { id: f, name: F, role: leader, ideology: fascism }\n  - { id: g, name: G, role: general, skill: 3 }\n",
+            ),
+            file(
+                "game/countries/GER/focus_tree.yaml",
+                "focuses:
+  - id: a
+    name: A
+    days: 1
+    effects:
+      - { type: set_flag, flag: rearming }
+      - { type: add_idea, idea: drive, days: 60 }
+      - { type: add_modifier, key: communism_drift, value: 0.01 }
+  - { id: b, name: B, days: 50 }
+",
+            ),
+            file("game/ideas/ideas.yaml", "ideas:\n  - { id: drive, name: Drive, modifiers: [{ key: manpower, value: 5 }] }\n"),
+            file(
+                "game/events/news.yaml",
+                "events:
+  - { id: e, title: E, date: 1936-01-03, options: [{ name: A }] }
+  - { id: m, title: M, date: 1936-01-10, mean_time_to_happen: 5, trigger: { tag: ITA }, options: [{ name: A, effects: [{ type: add_popularity, ideology: communism, value: 0.01 }] }] }
+",
+            ),
+            file("game/units/battalions.yaml", "battalions:\n  - { id: armor, name: Armor, stats: { hard_attack: 10 } }\n"),
+        ];
+        let content = ContentDb::from_files(&files);
+        let mut sim = Simulation::with_players(content.clone(), 5, BTreeSet::from(["GER".to_string()]));
+        sim.submit(SimInput::StartFocus { country: "GER".into(), focus: "a".into() });
+        sim.submit(SimInput::AssignGeneral { country: "GER".into(), general: "g".into(), divisions: vec![3] });
+        sim.run_until(StopWhen::Days(1));
+        sim.submit(SimInput::StartFocus { country: "GER".into(), focus: "b".into() });
+        sim.run_until(StopWhen::Date("1936-01-06 13:00".parse().unwrap()));
+        sim.state.divisions.insert(3, DivisionState { id: 3, location: 1, moving: Some(Moving { to: 2, hours_left: 40 }) });
+
+        sim.take_events();
         let dir = tempdir().unwrap();
         let path = dir.path().join("test_save.json");
-        save_to_path(&save, &path).unwrap();
-        let loaded = load_from_path(&path).unwrap();
-        assert_eq!(loaded.version, "0.1");
-        assert_eq!(loaded.day, 42);
-        assert_eq!(loaded.provinces.len(), 1);
-        assert_eq!(loaded.provinces[0].id, 7);
+        save_to_path(&GameSave::from_sim(&sim), &path).unwrap();
+        let mut loaded = Simulation::new(content.clone(), 99);
+        loaded.load_save(&load_from_path(&path).unwrap());
+
+        assert_eq!(loaded.clock.date().to_string(), "1936-01-06 13:00");
+        let ger = &loaded.state.countries["GER"];
+        assert!(ger.script.has_flag("rearming"));
+        assert!(ger.has_idea("drive"));
+        assert_eq!(ger.focus, sim.state.countries["GER"].focus);
+        assert_eq!(loaded.state.politics_save(), sim.state.politics_save());
+        assert_eq!(loaded.state.characters_save(), sim.state.characters_save());
+        assert_eq!(loaded.pending_events("GER"), sim.pending_events("GER"));
+        assert_eq!(loaded.state.divisions, sim.state.divisions);
+        assert_eq!(loaded.pending_events("GER")[0].event, "e");
+        assert_eq!(loaded.country_breakdown("GER", "manpower").value, 5.0);
+        assert_eq!(loaded.division_stats("GER", 3, "armor").unwrap().hard_attack, sim.division_stats("GER", 3, "armor").unwrap().hard_attack);
+
+        // Same random rolls and drift from here on
+        let choose = SimInput::ChooseEventOption { country: "GER".into(), event: "e".into(), option: 0 };
+        sim.submit(choose.clone());
+        loaded.submit(choose);
+        sim.run_until(StopWhen::Days(60));
+        loaded.run_until(StopWhen::Days(60));
+        assert_eq!(loaded.take_events(), sim.take_events());
+        assert_eq!(loaded.state.politics_save(), sim.state.politics_save());
+        assert!(loaded.state.countries["ITA"].events.fired.contains("m"));
+        assert_eq!(loaded.state.countries["GER"].focus, sim.state.countries["GER"].focus);
+        assert!(!loaded.state.countries["GER"].has_idea("drive"));
+
+        // The log spans the save, so the loaded game replays from the scenario start
+        assert_eq!(loaded.input_log(), sim.input_log());
+        let replayed = Simulation::replay(content, loaded.input_log(), loaded.clock.elapsed_hours());
+        assert_eq!(replayed.clock.date(), loaded.clock.date());
+        assert_eq!(replayed.rng.position(), loaded.rng.position());
+        assert_eq!(replayed.state.politics_save(), loaded.state.politics_save());
+        assert_eq!(replayed.state.characters_save(), loaded.state.characters_save());
+        assert_eq!(replayed.state.script_save(), loaded.state.script_save());
+        assert_eq!(replayed.state.countries["GER"].focus, loaded.state.countries["GER"].focus);
+        assert_eq!(replayed.state.countries["GER"].events, loaded.state.countries["GER"].events);
+        assert_eq!(replayed.state.countries["ITA"].events, loaded.state.countries["ITA"].events);
     }
 }
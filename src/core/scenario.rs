@@ -0,0 +1,13 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::core::time::GameDate;
+
+/// Scenario settings from `game/scenario.yaml`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ScenarioDef {
+    /// Display name of the scenario
+    pub name: String,
+    /// Date the game starts on, e.g. `1936-01-01`
+    pub start_date: GameDate,
+}
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Flags and numeric variables that content scripts keep for a country or
+/// for the whole game, set by effects and read by triggers.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScriptState {
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub flags: BTreeSet<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variables: BTreeMap<String, f64>,
+}
+
+impl ScriptState {
+    pub fn set_flag(&mut self, flag: &str) {
+        self.flags.insert(flag.to_string());
+    }
+
+    pub fn clear_flag(&mut self, flag: &str) {
+        self.flags.remove(flag);
+    }
+
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.flags.contains(flag)
+    }
+
+    /// Current value of `name`; variables nothing has set read as 0.
+    pub fn variable(&self, name: &str) -> f64 {
+        self.variables.get(name).copied().unwrap_or(0.0)
+    }
+
+    pub fn set_variable(&mut self, name: &str, value: f64) {
+        self.variables.insert(name.to_string(), value);
+    }
+
+    pub fn add_to_variable(&mut self, name: &str, value: f64) {
+        *self.variables.entry(name.to_string()).or_insert(0.0) += value;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.flags.is_empty() && self.variables.is_empty()
+    }
+}
+
+/// Script state of a whole game, as written to a save.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScriptSave {
+    #[serde(default)]
+    pub global: ScriptState,
+    /// Keyed by country tag; countries without flags or variables are left out
+    #[serde(default)]
+    pub countries: BTreeMap<String, ScriptState>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_toggle_and_variables_start_at_zero() {
+        let mut s = ScriptState::default();
+        assert!(!s.has_flag("anschluss_prepared"));
+        s.set_flag("anschluss_prepared");
+        assert!(s.has_flag("anschluss_prepared"));
+        s.clear_flag("anschluss_prepared");
+        assert!(s.is_empty());
+
+        assert_eq!(s.variable("tension"), 0.0);
+        s.add_to_variable("tension", 2.5);
+        s.add_to_variable("tension", 1.0);
+        assert_eq!(s.variable("tension"), 3.5);
+        s.set_variable("tension", -1.0);
+        assert_eq!(s.variable("tension"), -1.0);
+    }
+}
@@ -1,16 +1,38 @@
+// Headless simulation core: per-country state and a deterministic driver
+// that the Bevy systems and tests can both run.
+#[cfg(feature = "bevy")]
+use bevy::prelude::*;
+
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use crate::content::db::ContentDb;
+use crate::content::reload::{apply_live, LiveChange};
+use crate::core::characters::{CharacterDef, CharacterModifier, CharacterRole, CountryCharacters, SKILL_BONUS, SKILL_STATS};
+use crate::core::effects::{EffectContext, EffectRegistry};
+use crate::core::focus::RawEffect;
+use crate::core::events::{CountryEventState, EventDef, PendingEvent, Scope};
+use crate::core::ideas::{ActiveIdea, IdeaDef};
+use crate::core::modifiers::{Breakdown, Modifier, ModifierScope, ModifierSource, Modifiers, Stacking};
+use crate::core::politics::{drift_key, CivilWar, CountryPolitics, GovernmentChange};
+use crate::core::save::GameSave;
+use crate::core::script::{ScriptSave, ScriptState};
+use crate::core::time::{advance_clock_with, Clock, GameDate, Pulses, HOURS_PER_DAY};
+use crate::core::units::BattalionStats;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RunningFocus {
     pub id: String,
     pub remaining_days: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct CountryFocusState {
     pub in_progress: Option<RunningFocus>,
-    pub completed: HashSet<String>,
+    pub completed: BTreeSet<String>,
 }
 
 impl CountryFocusState {
@@ -36,16 +58,16 @@ impl CountryFocusState {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct RunningResearch {
     pub id: String,
     pub remaining_days: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct CountryResearchState {
     pub in_progress: Option<RunningResearch>,
-    pub completed: HashSet<String>,
+    pub completed: BTreeSet<String>,
 }
 
 impl CountryResearchState {
@@ -71,13 +93,14 @@ impl CountryResearchState {
 }
 
 /// Minimal division movement state used by the sim core
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Moving {
     pub to: u32,
-    pub days_left: u32,
+    /// Counted down once per in-game hour
+    pub hours_left: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DivisionState {
     pub id: u64,
     pub location: u32,
@@ -85,10 +108,11 @@ pub struct DivisionState {
 }
 
 impl DivisionState {
+    /// Advance movement by one hour. Returns true if the division arrived.
     pub fn tick(&mut self) -> bool {
         if let Some(ref mut m) = self.moving {
-            if m.days_left > 0 { m.days_left = m.days_left.saturating_sub(1); }
-            if m.days_left == 0 {
+            if m.hours_left > 0 { m.hours_left = m.hours_left.saturating_sub(1); }
+            if m.hours_left == 0 {
                 self.location = m.to;
                 self.moving = None;
                 return true; // arrived
@@ -98,6 +122,973 @@ impl DivisionState {
     }
 }
 
+/// Seeded random numbers for simulation code. ChaCha8 gives the same stream
+/// on every platform and `rand` release, so runs replay exactly.
+#[derive(Debug, Clone)]
+pub struct SimRng {
+    seed: u64,
+    rng: ChaCha8Rng,
+}
+
+impl SimRng {
+    pub fn new(seed: u64) -> Self {
+        SimRng { seed, rng: ChaCha8Rng::seed_from_u64(seed) }
+    }
+
+    /// The stream of `seed` from `position` on, as saved by [`SimRng::position`].
+    pub fn at(seed: u64, position: u128) -> Self {
+        let mut rng = SimRng::new(seed);
+        rng.rng.set_word_pos(position);
+        rng
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// How far into its stream the generator is, in 32-bit words.
+    pub fn position(&self) -> u128 {
+        self.rng.get_word_pos()
+    }
+}
+
+impl RngCore for SimRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+/// A change to the game made by a player or the AI.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SimInput {
+    StartFocus { country: String, focus: String },
+    StartResearch { country: String, tech: String },
+    /// Pick option `option` (0-based) of a pending event
+    ChooseEventOption { country: String, event: String, option: usize },
+    /// Hire an advisor from the roster for their political power cost
+    HireAdvisor { country: String, advisor: String },
+    DismissAdvisor { country: String, advisor: String },
+    /// Give a general command of these divisions; none relieves them
+    AssignGeneral { country: String, general: String, divisions: Vec<u64> },
+    /// Hand these countries to players and the rest to the AI, which
+    /// answers the events left waiting
+    SetPlayers { players: BTreeSet<String> },
+}
+
+/// An input and the hour it was submitted at (see [`Clock::elapsed_hours`]).
+/// It takes effect at the end of that hour, before the hour's pulses run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoggedInput {
+    pub hour: u64,
+    pub input: SimInput,
+}
+
+/// The seed and every input of a run: with the same content, enough to
+/// reproduce it exactly.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputLog {
+    pub seed: u64,
+    /// Countries played from the start; see [`GameState::players`]
+    #[serde(default)]
+    pub players: BTreeSet<String>,
+    pub inputs: Vec<LoggedInput>,
+}
+
+/// Something that happened during a run, for headless callers to react to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SimEvent {
+    FocusCompleted { country: String, focus: String },
+    ResearchCompleted { country: String, tech: String },
+    /// `option` is set when the AI picked one straight away
+    EventFired { country: String, event: String, option: Option<usize> },
+    ElectionHeld { country: String, winner: String },
+    /// The ruling ideology changed; `from` is `None` for a country that had none
+    GovernmentChanged { country: String, from: Option<String>, to: String, cause: GovernmentChange },
+    CivilWarStarted { country: String, ideology: String },
+    /// `rebels_won` when `ideology` took power, otherwise the government won
+    CivilWarEnded { country: String, ideology: String, rebels_won: bool },
+    /// `leader` is `None` when no character can lead the new government
+    LeaderChanged { country: String, leader: Option<String> },
+    /// A focus, tech, idea or event effect could not run; the rest still did
+    EffectFailed { country: String, effect: String, error: String },
+    DivisionArrived { division: u64, province: u32 },
+}
+
+/// The kind of a [`SimEvent`], for waiting on one with [`StopWhen::Event`].
+///
+/// There is no `WarDeclared` yet: the simulation has no wars between
+/// countries, so runs cannot stop on one. It belongs here once declaring war
+/// raises a [`SimEvent`]; until then [`SimEventKind::CivilWarStarted`] is the
+/// only war a run can wait for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SimEventKind {
+    FocusCompleted,
+    ResearchCompleted,
+    EventFired,
+    ElectionHeld,
+    GovernmentChanged,
+    CivilWarStarted,
+    CivilWarEnded,
+    LeaderChanged,
+    EffectFailed,
+    DivisionArrived,
+}
+
+impl SimEvent {
+    pub fn kind(&self) -> SimEventKind {
+        match self {
+            SimEvent::FocusCompleted { .. } => SimEventKind::FocusCompleted,
+            SimEvent::ResearchCompleted { .. } => SimEventKind::ResearchCompleted,
+            SimEvent::EventFired { .. } => SimEventKind::EventFired,
+            SimEvent::ElectionHeld { .. } => SimEventKind::ElectionHeld,
+            SimEvent::GovernmentChanged { .. } => SimEventKind::GovernmentChanged,
+            SimEvent::CivilWarStarted { .. } => SimEventKind::CivilWarStarted,
+            SimEvent::CivilWarEnded { .. } => SimEventKind::CivilWarEnded,
+            SimEvent::LeaderChanged { .. } => SimEventKind::LeaderChanged,
+            SimEvent::EffectFailed { .. } => SimEventKind::EffectFailed,
+            SimEvent::DivisionArrived { .. } => SimEventKind::DivisionArrived,
+        }
+    }
+}
+
+/// When [`Simulation::run_until`] should stop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopWhen {
+    /// At this date and hour; a date already passed stops at once
+    Date(GameDate),
+    /// After this many whole days
+    Days(u32),
+    /// After the hour in which an event of this kind fires, or after
+    /// `within_days` days if none does
+    Event { kind: SimEventKind, within_days: u32 },
+}
+
+/// How far a [`Simulation::run_until`] call got.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunOutcome {
+    pub hours: u64,
+    pub pulses: Pulses,
+    /// The event that stopped an [`StopWhen::Event`] run
+    pub event: Option<SimEvent>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CountryState {
+    pub focus: CountryFocusState,
+    pub research: CountryResearchState,
+    pub events: CountryEventState,
+    pub script: ScriptState,
+    /// National spirits, in the order they were added
+    pub ideas: Vec<ActiveIdea>,
+    pub politics: CountryPolitics,
+    pub characters: CountryCharacters,
+    /// Battalion types unlocked by `unlock_battalion` effects
+    #[serde(default)]
+    pub battalions: BTreeSet<String>,
+}
+
+impl CountryState {
+    pub fn has_idea(&self, id: &str) -> bool {
+        self.ideas.iter().any(|i| i.id == id)
+    }
+
+    pub fn idea(&self, id: &str) -> Option<&ActiveIdea> {
+        self.ideas.iter().find(|i| i.id == id)
+    }
+}
+
+/// Everything scripts can read and change, per country and game-wide.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GameState {
+    /// Keyed by tag; ordered so every pass visits countries the same way
+    pub countries: BTreeMap<String, CountryState>,
+    /// Game-wide flags and variables
+    pub global: ScriptState,
+    pub modifiers: Modifiers,
+    /// Countries whose events wait for a [`SimInput::ChooseEventOption`];
+    /// the AI picks for every other country as soon as an event fires
+    #[serde(default)]
+    pub players: BTreeSet<String>,
+    /// Divisions by id; movement advances every hour
+    #[serde(default)]
+    pub divisions: BTreeMap<u64, DivisionState>,
+}
+
+impl GameState {
+    /// Flags and variables for the game save.
+    pub fn script_save(&self) -> ScriptSave {
+        let countries = self
+            .countries
+            .iter()
+            .filter(|(_, c)| !c.script.is_empty())
+            .map(|(tag, c)| (tag.clone(), c.script.clone()))
+            .collect();
+        ScriptSave { global: self.global.clone(), countries }
+    }
+
+    /// Restore flags and variables from a save; countries it does not list are cleared.
+    pub fn load_script(&mut self, save: &ScriptSave) {
+        self.global = save.global.clone();
+        for (tag, c) in &mut self.countries {
+            c.script = save.countries.get(tag).cloned().unwrap_or_default();
+        }
+    }
+
+    /// Give `country` the idea `def` and its modifiers. A country that
+    /// already has it gets it afresh, so a timed idea restarts its clock.
+    pub fn add_idea(&mut self, country: &str, def: &IdeaDef, expires: Option<GameDate>) -> bool {
+        self.remove_idea(country, &def.id);
+        let Some(state) = self.countries.get_mut(country) else { return false };
+        state.ideas.push(ActiveIdea { id: def.id.clone(), expires });
+        for m in &def.modifiers {
+            let scope = match &m.unit {
+                Some(unit) => ModifierScope::UnitType { country: country.to_string(), unit: unit.clone() },
+                None => ModifierScope::Country(country.to_string()),
+            };
+            let source = ModifierSource::Idea(def.id.clone());
+            self.modifiers.add(Modifier { key: m.key.clone(), value: m.value, stacking: m.stacking, source, scope, expires });
+        }
+        true
+    }
+
+    /// Take the idea `id` and its modifiers away from `country`. Returns
+    /// whether the country had it.
+    pub fn remove_idea(&mut self, country: &str, id: &str) -> bool {
+        let Some(state) = self.countries.get_mut(country) else { return false };
+        let before = state.ideas.len();
+        state.ideas.retain(|i| i.id != id);
+        self.modifiers.remove_source_for(country, &ModifierSource::Idea(id.to_string()));
+        state.ideas.len() != before
+    }
+
+    /// Ideas of every country that has any, for the game save.
+    pub fn ideas_save(&self) -> BTreeMap<String, Vec<ActiveIdea>> {
+        self.countries
+            .iter()
+            .filter(|(_, c)| !c.ideas.is_empty())
+            .map(|(tag, c)| (tag.clone(), c.ideas.clone()))
+            .collect()
+    }
+
+    /// Restore ideas from a save, re-creating their modifiers from `content`.
+    /// Ideas the content no longer defines are dropped.
+    pub fn load_ideas(&mut self, saved: &BTreeMap<String, Vec<ActiveIdea>>, content: &ContentDb) {
+        let held: Vec<(String, String)> = self
+            .countries
+            .iter()
+            .flat_map(|(tag, c)| c.ideas.iter().map(move |i| (tag.clone(), i.id.clone())))
+            .collect();
+        for (tag, id) in held {
+            self.remove_idea(&tag, &id);
+        }
+        for (tag, ideas) in saved {
+            for idea in ideas {
+                if let Some(def) = content.idea(&idea.id) {
+                    self.add_idea(tag, def, idea.expires);
+                }
+            }
+        }
+    }
+
+    /// Government and popularity of every country, for the game save.
+    pub fn politics_save(&self) -> BTreeMap<String, CountryPolitics> {
+        self.countries.iter().map(|(tag, c)| (tag.clone(), c.politics.clone())).collect()
+    }
+
+    /// Restore politics from a save; countries it does not list keep theirs.
+    /// Ideologies the save leaves out stay listed at zero support.
+    pub fn load_politics(&mut self, saved: &BTreeMap<String, CountryPolitics>) {
+        for (tag, politics) in saved {
+            if let Some(c) = self.countries.get_mut(tag) {
+                let listed = std::mem::replace(&mut c.politics, politics.clone()).popularity.into_keys();
+                for id in listed {
+                    c.politics.popularity.entry(id).or_insert(0.0);
+                }
+            }
+        }
+    }
+
+    /// Put `def` on `country`'s roster. A general's skill and traits start
+    /// applying to the divisions they will command.
+    pub fn add_character(&mut self, country: &str, def: &CharacterDef, content: &ContentDb) -> bool {
+        let Some(state) = self.countries.get_mut(country) else { return false };
+        if !state.characters.roster.insert(def.id.clone()) {
+            return false;
+        }
+        if def.role == CharacterRole::General {
+            let source = ModifierSource::Character(def.id.clone());
+            let scope = ModifierScope::General { country: country.to_string(), general: def.id.clone() };
+            let skill = SKILL_STATS.iter().map(|stat| CharacterModifier {
+                key: stat.to_string(),
+                value: SKILL_BONUS * f64::from(def.skill),
+                stacking: Stacking::Multiply,
+            });
+            let traits = def.traits.iter().filter_map(|t| content.character_trait(t)).flat_map(|t| t.modifiers.iter().cloned());
+            for m in skill.chain(traits) {
+                let (source, scope) = (source.clone(), scope.clone());
+                self.modifiers.add(Modifier { key: m.key, value: m.value, stacking: m.stacking, source, scope, expires: None });
+            }
+        }
+        true
+    }
+
+    /// Take `id` off `country`'s roster and out of any post, with everything
+    /// it granted.
+    pub fn remove_character(&mut self, country: &str, id: &str) -> bool {
+        let Some(state) = self.countries.get_mut(country) else { return false };
+        if !state.characters.roster.contains(id) {
+            return false;
+        }
+        state.characters.remove(id);
+        self.modifiers.remove_source_for(country, &ModifierSource::Character(id.to_string()));
+        true
+    }
+
+    /// Make `def` the leader of `country`, adding them to the roster first
+    /// if needed. The previous leader stays on the roster.
+    pub fn appoint_leader(&mut self, country: &str, def: &CharacterDef, content: &ContentDb) -> bool {
+        self.add_character(country, def, content);
+        let Some(state) = self.countries.get_mut(country) else { return false };
+        let previous = state.characters.leader.replace(def.id.clone());
+        let scope = ModifierScope::Country(country.to_string());
+        if let Some(previous) = previous {
+            self.modifiers.remove_scoped(&ModifierSource::Character(previous), &scope);
+        }
+        self.add_office_modifiers(&scope, def);
+        true
+    }
+
+    /// Replace a leader who does not lead the ruling ideology with the first
+    /// one on the roster who does. Returns whether the leader changed.
+    pub fn refresh_leader(&mut self, country: &str, content: &ContentDb) -> bool {
+        let Some(state) = self.countries.get(country) else { return false };
+        let ruling = state.politics.ruling.as_deref();
+        let fits = |def: &CharacterDef| def.role == CharacterRole::Leader && def.ideology.as_deref().is_none_or(|i| Some(i) == ruling);
+        let current = state.characters.leader.as_deref().and_then(|id| content.character(id));
+        // Promoted generals and advisors lead whoever governs
+        if current.is_some_and(|def| def.role != CharacterRole::Leader || fits(def)) {
+            return false;
+        }
+        let next = content.characters.values().find(|def| state.characters.roster.contains(&def.id) && fits(def));
+        match next {
+            Some(def) => self.appoint_leader(country, def, content),
+            None => {
+                let Some(state) = self.countries.get_mut(country) else { return false };
+                let Some(previous) = state.characters.leader.take() else { return false };
+                let scope = ModifierScope::Country(country.to_string());
+                self.modifiers.remove_scoped(&ModifierSource::Character(previous), &scope);
+                true
+            }
+        }
+    }
+
+    /// Hire `def` as an advisor of `country` if it is on the roster and the
+    /// country can pay for it.
+    pub fn hire_advisor(&mut self, country: &str, def: &CharacterDef) -> bool {
+        let Some(state) = self.countries.get_mut(country) else { return false };
+        let chars = &mut state.characters;
+        if def.role != CharacterRole::Advisor || !chars.roster.contains(&def.id) || chars.advisors.contains(&def.id) {
+            return false;
+        }
+        if state.politics.political_power < def.cost {
+            return false;
+        }
+        state.politics.political_power -= def.cost;
+        chars.advisors.insert(def.id.clone());
+        self.add_office_modifiers(&ModifierScope::Country(country.to_string()), def);
+        true
+    }
+
+    pub fn dismiss_advisor(&mut self, country: &str, id: &str) -> bool {
+        let Some(state) = self.countries.get_mut(country) else { return false };
+        if !state.characters.advisors.remove(id) {
+            return false;
+        }
+        self.modifiers.remove_scoped(&ModifierSource::Character(id.to_string()), &ModifierScope::Country(country.to_string()));
+        true
+    }
+
+    fn add_office_modifiers(&mut self, scope: &ModifierScope, def: &CharacterDef) {
+        for m in &def.modifiers {
+            let source = ModifierSource::Character(def.id.clone());
+            let scope = scope.clone();
+            self.modifiers.add(Modifier { key: m.key.clone(), value: m.value, stacking: m.stacking, source, scope, expires: None });
+        }
+    }
+
+    /// Rosters and posts of every country, for the game save.
+    pub fn characters_save(&self) -> BTreeMap<String, CountryCharacters> {
+        self.countries.iter().map(|(tag, c)| (tag.clone(), c.characters.clone())).collect()
+    }
+
+    /// Restore rosters and posts from a save, re-creating their modifiers
+    /// from `content`. Characters the content no longer defines are dropped.
+    pub fn load_characters(&mut self, saved: &BTreeMap<String, CountryCharacters>, content: &ContentDb) {
+        for (tag, chars) in saved {
+            let Some(state) = self.countries.get_mut(tag) else { continue };
+            let roster = std::mem::take(&mut state.characters).roster;
+            for id in roster {
+                self.modifiers.remove_source_for(tag, &ModifierSource::Character(id));
+            }
+            for def in chars.roster.iter().filter_map(|id| content.character(id)) {
+                self.add_character(tag, def, content);
+            }
+            if let Some(def) = chars.leader.as_deref().and_then(|id| content.character(id)) {
+                self.appoint_leader(tag, def, content);
+            }
+            let scope = ModifierScope::Country(tag.clone());
+            for def in chars.advisors.iter().filter_map(|id| content.character(id)) {
+                if let Some(state) = self.countries.get_mut(tag) {
+                    state.characters.advisors.insert(def.id.clone());
+                }
+                self.add_office_modifiers(&scope, def);
+            }
+            if let Some(state) = self.countries.get_mut(tag) {
+                let known = chars.commands.iter().filter(|(general, _)| content.character(general).is_some());
+                state.characters.commands = known.map(|(g, d)| (g.clone(), d.clone())).collect();
+            }
+        }
+    }
+
+    /// Trigger scope of `tag`, if it is a known country.
+    pub fn scope<'a>(&'a self, tag: &'a str, date: GameDate) -> Option<Scope<'a>> {
+        let country = self.countries.get(tag)?;
+        Some(Scope { tag, country, global: &self.global, date })
+    }
+}
+
+/// Runs the game without Bevy in fixed one-hour steps. The outcome depends
+/// only on the content, the seed and the logged inputs, never on how the
+/// hours were split across frames.
+#[cfg_attr(feature = "bevy", derive(Resource))]
+pub struct Simulation {
+    pub clock: Clock,
+    pub rng: SimRng,
+    pub state: GameState,
+    rules: Rules,
+    log: InputLog,
+    /// Submitted inputs not yet applied, oldest first
+    pending: VecDeque<LoggedInput>,
+    /// Events since the last [`Simulation::take_events`]
+    events: Vec<SimEvent>,
+}
+
+/// What a step reads but never changes.
+struct Rules {
+    content: ContentDb,
+    effects: EffectRegistry,
+}
+
+impl Simulation {
+    /// A running clock at the scenario start with every country idle and
+    /// AI-controlled.
+    pub fn new(content: ContentDb, seed: u64) -> Self {
+        Self::with_players(content, seed, BTreeSet::new())
+    }
+
+    /// Like [`Simulation::new`], with `players` choosing their own event options.
+    pub fn with_players(content: ContentDb, seed: u64, players: BTreeSet<String>) -> Self {
+        let start = content.start_date();
+        let countries = content
+            .countries
+            .iter()
+            .map(|(tag, def)| {
+                let elections = def.ideology.as_deref().is_some_and(|i| content.holds_elections(i));
+                let mut politics = CountryPolitics::starting(def, &content.politics, elections, start);
+                // Every ideology is listed, so support given up always has somewhere to go
+                for id in content.ideologies.keys() {
+                    politics.popularity.entry(id.clone()).or_insert(0.0);
+                }
+                (tag.clone(), CountryState { politics, ..CountryState::default() })
+            })
+            .collect();
+        let mut state = GameState { countries, players: players.clone(), ..GameState::default() };
+        for tag in content.countries.keys() {
+            for def in content.characters_of(tag) {
+                state.add_character(tag, def, &content);
+            }
+            state.refresh_leader(tag, &content);
+        }
+        Simulation {
+            clock: Clock::starting(content.start_date()),
+            rng: SimRng::new(seed),
+            state,
+            rules: Rules { content, effects: EffectRegistry::core() },
+            log: InputLog { seed, players, inputs: Vec::new() },
+            pending: VecDeque::new(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Rebuild a run from its log, simulating `hours` hours.
+    pub fn replay(content: ContentDb, log: &InputLog, hours: u64) -> Self {
+        let mut sim = Simulation::with_players(content, log.seed, log.players.clone());
+        sim.pending.extend(log.inputs.iter().cloned());
+        sim.log.inputs = log.inputs.clone();
+        sim.step_hours(hours);
+        sim
+    }
+
+    pub fn content(&self) -> &ContentDb {
+        &self.rules.content
+    }
+
+    /// Pick up content changes from a hot reload (see
+    /// [`reload_into`](crate::content::reload::reload_into)). A running focus
+    /// whose length changed keeps the days already spent on it.
+    pub fn apply_live(&mut self, changes: &[LiveChange]) {
+        apply_live(&mut self.rules.content, changes);
+        for change in changes {
+            let LiveChange::FocusDays { country, focus, old, new } = change else { continue };
+            let running = self.state.countries.get_mut(country).and_then(|c| c.focus.in_progress.as_mut());
+            if let Some(running) = running.filter(|running| running.id == *focus) {
+                let elapsed = old.saturating_sub(running.remaining_days);
+                running.remaining_days = new.saturating_sub(elapsed).max(1);
+            }
+        }
+    }
+
+    /// Carry on from `save` with this simulation's content, speed and pause
+    /// state. Countries the content no longer has are dropped, and countries
+    /// the save does not list start afresh. The input log carries on from the
+    /// saved one, so [`Simulation::replay`] from the scenario start reaches
+    /// the same state; saves without a log keep only their pending inputs.
+    pub fn load_save(&mut self, save: &GameSave) {
+        let content = std::mem::take(&mut self.rules.content);
+        let mut sim = Simulation::with_players(content, save.seed, save.players.clone());
+        for (tag, progress) in &save.progress {
+            if let Some(c) = sim.state.countries.get_mut(tag) {
+                c.focus = progress.focus.clone();
+                c.research = progress.research.clone();
+                c.events = progress.events.clone();
+                c.battalions = progress.battalions.clone();
+            }
+        }
+        sim.state.divisions = save.divisions.clone();
+        sim.state.load_script(&save.script);
+        sim.state.load_politics(&save.politics);
+        sim.state.load_ideas(&save.ideas, &sim.rules.content);
+        sim.state.load_characters(&save.characters, &sim.rules.content);
+        for m in &save.modifiers {
+            sim.state.modifiers.add(m.clone());
+        }
+        sim.rng = SimRng::at(save.seed, save.rng_position);
+        sim.clock = Clock { current_day: save.day, hour: save.date.hour(), acc: 0.0, run_until: None, ..self.clock.clone() };
+        sim.pending.extend(save.inputs.iter().cloned());
+        match &save.log {
+            Some(log) => sim.log = log.clone(),
+            None => sim.log.inputs.extend(save.inputs.iter().cloned()),
+        }
+        *self = sim;
+    }
+
+    /// Effective value of a country-wide `key` such as `civ_factories`, with
+    /// the base taken from the country's `resources` plus what its provinces
+    /// provide (see [`Simulation::province_breakdown`]).
+    pub fn country_breakdown(&self, country: &str, key: &str) -> Breakdown {
+        let base = country_base(&self.rules.content, &self.state.modifiers, country, key);
+        self.state.modifiers.breakdown(key, base, &[ModifierScope::Country(country.to_string())])
+    }
+
+    /// What `province` adds to its owner's `key`, from modifiers on the
+    /// province alone. Provinces have no base values in content, so this
+    /// starts from zero.
+    pub fn province_breakdown(&self, province: u32, key: &str) -> Breakdown {
+        self.state.modifiers.breakdown(key, 0.0, &[ModifierScope::Province(province)])
+    }
+
+    /// Effective `stat` of `country`'s `unit` battalions, for combat.
+    pub fn unit_stat_breakdown(&self, country: &str, unit: &str, stat: &str) -> Option<Breakdown> {
+        let base = self.rules.content.battalion(unit)?.stats.get(stat)?;
+        Some(self.state.modifiers.breakdown(stat, f64::from(base), &unit_scopes(country, unit)))
+    }
+
+    /// All stats of `country`'s `unit` battalions with modifiers applied.
+    pub fn battalion_stats(&self, country: &str, unit: &str) -> Option<BattalionStats> {
+        self.stats_with(unit, &unit_scopes(country, unit))
+    }
+
+    /// Like [`Simulation::unit_stat_breakdown`], for `unit` battalions in
+    /// `division`, including the bonuses of the general commanding it.
+    pub fn division_stat_breakdown(&self, country: &str, division: u64, unit: &str, stat: &str) -> Option<Breakdown> {
+        let base = self.rules.content.battalion(unit)?.stats.get(stat)?;
+        Some(self.state.modifiers.breakdown(stat, f64::from(base), &self.division_scopes(country, division, unit)))
+    }
+
+    /// Like [`Simulation::battalion_stats`], for `unit` battalions in `division`.
+    pub fn division_stats(&self, country: &str, division: u64, unit: &str) -> Option<BattalionStats> {
+        self.stats_with(unit, &self.division_scopes(country, division, unit))
+    }
+
+    fn division_scopes(&self, country: &str, division: u64, unit: &str) -> Vec<ModifierScope> {
+        let mut scopes = unit_scopes(country, unit).to_vec();
+        let general = self.state.countries.get(country).and_then(|c| c.characters.commander(division));
+        scopes.extend(general.map(|g| ModifierScope::General { country: country.to_string(), general: g.to_string() }));
+        scopes
+    }
+
+    fn stats_with(&self, unit: &str, scopes: &[ModifierScope]) -> Option<BattalionStats> {
+        let mut stats = self.rules.content.battalion(unit)?.stats.clone();
+        for name in BattalionStats::NAMES {
+            if let Some(v) = stats.get_mut(name) {
+                *v = self.state.modifiers.value(name, f64::from(*v), scopes) as f32;
+            }
+        }
+        Some(stats)
+    }
+
+    /// Events waiting for `country` to choose an option, oldest first.
+    pub fn pending_events(&self, country: &str) -> &[PendingEvent] {
+        self.state.countries.get(country).map(|c| c.events.pending.as_slice()).unwrap_or(&[])
+    }
+
+    pub fn input_log(&self) -> &InputLog {
+        &self.log
+    }
+
+    /// Submitted inputs that take effect at the end of the current hour.
+    pub fn pending_inputs(&self) -> impl Iterator<Item = &LoggedInput> {
+        self.pending.iter()
+    }
+
+    /// Drain the events raised since the last call, in the order they happened.
+    pub fn take_events(&mut self) -> Vec<SimEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Switch which countries players control, as a logged input so
+    /// replays switch at the same hour.
+    pub fn set_players(&mut self, players: BTreeSet<String>) {
+        self.submit(SimInput::SetPlayers { players });
+    }
+
+    /// Queue `input` for the current hour and record it in the log.
+    pub fn submit(&mut self, input: SimInput) {
+        let logged = LoggedInput { hour: self.clock.elapsed_hours(), input };
+        self.log.inputs.push(logged.clone());
+        self.pending.push_back(logged);
+    }
+
+    /// Frame-driven advance for real-time play; see [`advance_clock_with`].
+    pub fn advance(&mut self, delta_seconds: f32) -> Pulses {
+        let Simulation { clock, rng, state, rules, pending, events, .. } = self;
+        advance_clock_with(clock, delta_seconds, |clock, pulses| {
+            run_hour(clock, pulses, rng, state, rules, pending, events);
+        })
+    }
+
+    /// Advance exactly `hours` hours, ignoring pause and speed.
+    pub fn step_hours(&mut self, hours: u64) -> Pulses {
+        let mut total = Pulses::default();
+        for _ in 0..hours {
+            total += self.step_hour();
+        }
+        total
+    }
+
+    /// Run as fast as possible until `stop`, ignoring pause and speed, for
+    /// balance runs and fast-forwarding. Events stay queued for
+    /// [`Simulation::take_events`].
+    pub fn run_until(&mut self, stop: StopWhen) -> RunOutcome {
+        let days_in_hours = |days: u32| u64::from(days) * u64::from(HOURS_PER_DAY);
+        let (hours, kind) = match stop {
+            StopWhen::Date(date) => (date.hours_since(self.clock.date()).max(0) as u64, None),
+            StopWhen::Days(days) => (days_in_hours(days), None),
+            StopWhen::Event { kind, within_days } => (days_in_hours(within_days), Some(kind)),
+        };
+        let mut outcome = RunOutcome { hours: 0, pulses: Pulses::default(), event: None };
+        while outcome.hours < hours {
+            let seen = self.events.len();
+            outcome.pulses += self.step_hour();
+            outcome.hours += 1;
+            if let Some(kind) = kind {
+                outcome.event = self.events[seen..].iter().find(|e| e.kind() == kind).cloned();
+                if outcome.event.is_some() {
+                    break;
+                }
+            }
+        }
+        outcome
+    }
+
+    fn step_hour(&mut self) -> Pulses {
+        let pulses = self.clock.advance_hour();
+        run_hour(&self.clock, pulses, &mut self.rng, &mut self.state, &self.rules, &mut self.pending, &mut self.events);
+        pulses
+    }
+}
+
+/// Base of a country-wide value: the country's `resources`, or 0, plus the
+/// province modifiers on every province it owns.
+fn country_base(content: &ContentDb, modifiers: &Modifiers, country: &str, key: &str) -> f64 {
+    let resource = content.country(country).and_then(|c| c.resources.as_ref()?.get(key)?.as_f64()).unwrap_or(0.0);
+    let owned = content.provinces.keys().filter(|&&p| content.owner_of(p).is_some_and(|c| c.tag == country));
+    resource + owned.map(|&p| modifiers.value(key, 0.0, &[ModifierScope::Province(p)])).sum::<f64>()
+}
+
+/// Modifiers covering one unit type: the country's own plus the unit's.
+fn unit_scopes(country: &str, unit: &str) -> [ModifierScope; 2] {
+    [
+        ModifierScope::Country(country.to_string()),
+        ModifierScope::UnitType { country: country.to_string(), unit: unit.to_string() },
+    ]
+}
+
+/// One fixed step, after the clock has moved to its end.
+fn run_hour(
+    clock: &Clock,
+    pulses: Pulses,
+    rng: &mut SimRng,
+    state: &mut GameState,
+    rules: &Rules,
+    pending: &mut VecDeque<LoggedInput>,
+    events: &mut Vec<SimEvent>,
+) {
+    while pending.front().is_some_and(|p| p.hour < clock.elapsed_hours()) {
+        let Some(logged) = pending.pop_front() else { break };
+        apply_input(state, rules, rng, events, clock.date(), logged.input);
+    }
+    for (id, division) in state.divisions.iter_mut() {
+        if division.tick() {
+            events.push(SimEvent::DivisionArrived { division: *id, province: division.location });
+        }
+    }
+    if pulses.days > 0 {
+        let date = clock.date();
+        state.modifiers.expire(date);
+        for country in state.countries.values_mut() {
+            country.ideas.retain(|i| i.expires.is_none_or(|e| e > date));
+        }
+        let mut completed = Vec::new();
+        for (tag, country) in state.countries.iter_mut() {
+            if let Some(focus) = country.focus.tick() {
+                completed.push(SimEvent::FocusCompleted { country: tag.clone(), focus });
+            }
+            if let Some(tech) = country.research.tick() {
+                completed.push(SimEvent::ResearchCompleted { country: tag.clone(), tech });
+            }
+        }
+        for done in completed {
+            let content = &rules.content;
+            let (country, source, effects) = match &done {
+                SimEvent::FocusCompleted { country, focus } => {
+                    let def = content.focus_tree(country).and_then(|t| t.find(focus));
+                    (country, ModifierSource::Focus(focus.clone()), def.map(|f| f.effects.as_slice()))
+                }
+                SimEvent::ResearchCompleted { country, tech } => {
+                    (country, ModifierSource::Tech(tech.clone()), content.tech(tech).map(|t| t.effects.as_slice()))
+                }
+                _ => continue,
+            };
+            events.push(done.clone());
+            run_effects(state, rules, events, country, date, source, effects.unwrap_or(&[]));
+        }
+        for (tag, country) in state.countries.iter_mut() {
+            run_politics(tag, country, &state.modifiers, &rules.content, date, events);
+        }
+        let tags: Vec<String> = state.countries.keys().cloned().collect();
+        for tag in tags {
+            if state.refresh_leader(&tag, &rules.content) {
+                let leader = state.countries[&tag].characters.leader.clone();
+                events.push(SimEvent::LeaderChanged { country: tag, leader });
+            }
+        }
+        fire_events(date, rng, state, rules, events);
+    }
+}
+
+/// Daily politics for one country: popularity drifts under its
+/// `<ideology>_drift` modifiers, then democracies hold any election that is
+/// due while other governments face coups and civil wars.
+fn run_politics(tag: &str, country: &mut CountryState, modifiers: &Modifiers, content: &ContentDb, date: GameDate, events: &mut Vec<SimEvent>) {
+    let politics = &mut country.politics;
+    let scope = [ModifierScope::Country(tag.to_string())];
+    politics.political_power += modifiers.value("political_power_gain", country_base(content, modifiers, tag, "political_power_gain"), &scope);
+    let Some(ruling) = politics.ruling.clone() else { return };
+    for id in content.ideologies.keys() {
+        let drift = modifiers.value(&drift_key(id), 0.0, &scope);
+        if drift != 0.0 {
+            politics.add_popularity(id, drift);
+        }
+    }
+
+    let rules = &content.politics;
+    let before = politics.clone();
+    let mut cause = GovernmentChange::Coup;
+    if content.holds_elections(&ruling) {
+        if politics.next_election.is_some_and(|d| d <= date) {
+            let winner = politics.most_popular().map_or(ruling, |(id, _)| id.to_string());
+            events.push(SimEvent::ElectionHeld { country: tag.to_string(), winner: winner.clone() });
+            cause = GovernmentChange::Election;
+            politics.set_ruling(&winner, content.holds_elections(&winner), rules, date);
+        }
+    } else if let Some((rebels, support)) = politics.strongest_opposition().map(|(id, v)| (id.to_string(), v)) {
+        let at_war_with = politics.civil_war.as_ref().map(|w| w.ideology.clone());
+        if support >= rules.coup_threshold {
+            if at_war_with.as_ref() == Some(&rebels) {
+                cause = GovernmentChange::CivilWar;
+            }
+            politics.set_ruling(&rebels, content.holds_elections(&rebels), rules, date);
+        } else if let Some(war) = at_war_with {
+            if politics.popularity(&war) < rules.civil_war_threshold {
+                politics.civil_war = None;
+            }
+        } else if support >= rules.civil_war_threshold {
+            politics.civil_war = Some(CivilWar { ideology: rebels, started: date });
+        }
+    }
+    events.extend(government_news(tag, &before, politics, cause));
+}
+
+/// Events for the difference between two states of a country's politics.
+fn government_news(tag: &str, before: &CountryPolitics, after: &CountryPolitics, cause: GovernmentChange) -> Vec<SimEvent> {
+    let country = || tag.to_string();
+    let mut news = Vec::new();
+    if let (Some(to), true) = (&after.ruling, before.ruling != after.ruling) {
+        news.push(SimEvent::GovernmentChanged { country: country(), from: before.ruling.clone(), to: to.clone(), cause });
+    }
+    match (&before.civil_war, &after.civil_war) {
+        (None, Some(war)) => news.push(SimEvent::CivilWarStarted { country: country(), ideology: war.ideology.clone() }),
+        (Some(war), None) => {
+            let rebels_won = after.ruling.as_ref() == Some(&war.ideology);
+            news.push(SimEvent::CivilWarEnded { country: country(), ideology: war.ideology.clone(), rebels_won });
+        }
+        _ => {}
+    }
+    news
+}
+
+/// Daily event check: every country, then every event, in a fixed order so
+/// the random rolls replay.
+fn fire_events(date: GameDate, rng: &mut SimRng, state: &mut GameState, rules: &Rules, events: &mut Vec<SimEvent>) {
+    let tags: Vec<String> = state.countries.keys().cloned().collect();
+    for tag in tags {
+        for def in rules.content.events.values() {
+            if !state.scope(&tag, date).is_some_and(|scope| def.eligible(&scope)) {
+                continue;
+            }
+            if def.mean_time_to_happen.is_some_and(|mtth| !rng.gen_bool(1.0 / f64::from(mtth.max(1)))) {
+                continue;
+            }
+            let Some(country) = state.countries.get_mut(&tag) else { continue };
+            country.events.fired.insert(def.id.clone());
+            if state.players.contains(&tag) {
+                country.events.pending.push(PendingEvent { event: def.id.clone(), fired: date });
+                events.push(SimEvent::EventFired { country: tag.clone(), event: def.id.clone(), option: None });
+                continue;
+            }
+            let option = ai_option(def, rng);
+            events.push(SimEvent::EventFired { country: tag.clone(), event: def.id.clone(), option: Some(option) });
+            let source = ModifierSource::Event(def.id.clone());
+            run_effects(state, rules, events, &tag, date, source, &def.options[option].effects);
+        }
+    }
+}
+
+/// The option the AI picks for `def`. All-zero weights leave it with the first.
+fn ai_option(def: &EventDef, rng: &mut SimRng) -> usize {
+    WeightedIndex::new(def.options.iter().map(|o| o.ai_weight)).map_or(0, |w| w.sample(rng))
+}
+
+/// Run effects for `country`. An effect that fails is skipped; content
+/// validation reports unknown effect types ahead of time.
+fn run_effects(
+    state: &mut GameState,
+    rules: &Rules,
+    events: &mut Vec<SimEvent>,
+    country: &str,
+    date: GameDate,
+    source: ModifierSource,
+    effects: &[RawEffect],
+) {
+    let before = state.countries.get(country).map(|c| (c.politics.clone(), c.characters.leader.clone()));
+    let mut ctx = EffectContext { country, state, content: &rules.content, date, source };
+    for effect in effects {
+        if let Err(e) = rules.effects.apply(&mut ctx, effect) {
+            events.push(SimEvent::EffectFailed { country: country.to_string(), effect: effect.kind.clone(), error: format!("{:#}", e) });
+        }
+    }
+    state.refresh_leader(country, &rules.content);
+    if let (Some((politics, leader)), Some(after)) = (before, state.countries.get(country)) {
+        events.extend(government_news(country, &politics, &after.politics, GovernmentChange::Effect));
+        if leader != after.characters.leader {
+            events.push(SimEvent::LeaderChanged { country: country.to_string(), leader: after.characters.leader.clone() });
+        }
+    }
+}
+
+/// Apply one input. Inputs the content cannot satisfy are ignored, the same
+/// way on every replay.
+fn apply_input(state: &mut GameState, rules: &Rules, rng: &mut SimRng, events: &mut Vec<SimEvent>, date: GameDate, input: SimInput) {
+    let content = &rules.content;
+    match input {
+        SimInput::StartFocus { country, focus } => {
+            let days = content.focus_tree(&country).and_then(|t| t.find(&focus)).map(|f| f.days);
+            if let (Some(c), Some(days)) = (state.countries.get_mut(&country), days) {
+                let _ = c.focus.start_focus(focus, days);
+            }
+        }
+        SimInput::StartResearch { country, tech } => {
+            // A `research_speed` of 0.25 means 25% faster
+            let bonus = state.modifiers.value("research_speed", 0.0, &[ModifierScope::Country(country.clone())]);
+            let days = content.tech(&tech).map(|t| (f64::from(t.days) / (1.0 + bonus).max(0.1)).ceil() as u32);
+            if let (Some(c), Some(days)) = (state.countries.get_mut(&country), days) {
+                let _ = c.research.start_research(tech, days);
+            }
+        }
+        SimInput::ChooseEventOption { country, event, option } => {
+            let Some(def) = content.event(&event).filter(|e| option < e.options.len()) else { return };
+            let Some(c) = state.countries.get_mut(&country) else { return };
+            let Some(at) = c.events.pending.iter().position(|p| p.event == event) else { return };
+            c.events.pending.remove(at);
+            run_effects(state, rules, events, &country, date, ModifierSource::Event(event), &def.options[option].effects);
+        }
+        SimInput::HireAdvisor { country, advisor } => {
+            if let Some(def) = content.character(&advisor) {
+                state.hire_advisor(&country, def);
+            }
+        }
+        SimInput::DismissAdvisor { country, advisor } => {
+            state.dismiss_advisor(&country, &advisor);
+        }
+        SimInput::AssignGeneral { country, general, divisions } => {
+            let is_general = content.character(&general).is_some_and(|c| c.role == CharacterRole::General);
+            let Some(c) = state.countries.get_mut(&country) else { return };
+            if is_general && c.characters.roster.contains(&general) {
+                c.characters.assign(&general, &divisions.into_iter().collect());
+            }
+        }
+        SimInput::SetPlayers { players } => {
+            let released: Vec<String> = state.players.difference(&players).cloned().collect();
+            state.players = players;
+            for tag in released {
+                let Some(c) = state.countries.get_mut(&tag) else { continue };
+                for waiting in std::mem::take(&mut c.events.pending) {
+                    let Some(def) = content.event(&waiting.event) else { continue };
+                    let option = ai_option(def, rng);
+                    let source = ModifierSource::Event(def.id.clone());
+                    run_effects(state, rules, events, &tag, date, source, &def.options[option].effects);
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -124,11 +1115,397 @@ mod tests {
 
     #[test]
     fn division_movement_arrival() {
-        let mut d = DivisionState { id: 1, location: 0, moving: Some(Moving{ to: 2, days_left: 2 }) };
+        let mut d = DivisionState { id: 1, location: 0, moving: Some(Moving{ to: 2, hours_left: 2 }) };
         assert!(!d.tick());
-        assert_eq!(d.moving.as_ref().unwrap().days_left, 1);
+        assert_eq!(d.moving.as_ref().unwrap().hours_left, 1);
         assert!(d.tick());
         assert_eq!(d.location, 2);
         assert!(d.moving.is_none());
     }
+
+    #[test]
+    fn divisions_arrive_in_the_middle_of_a_day() {
+        let mut sim = Simulation::new(content(), 1);
+        let moving = Moving { to: 7, hours_left: 30 };
+        sim.state.divisions.insert(1, DivisionState { id: 1, location: 3, moving: Some(moving) });
+        let outcome = sim.run_until(StopWhen::Event { kind: SimEventKind::DivisionArrived, within_days: 2 });
+        assert_eq!(outcome.event, Some(SimEvent::DivisionArrived { division: 1, province: 7 }));
+        assert_eq!(outcome.hours, 30);
+        assert_eq!(sim.clock.date().to_string(), "1936-01-02 06:00");
+        assert_eq!(sim.state.divisions[&1].location, 7);
+    }
+
+    fn content() -> ContentDb {
+        ContentDb::from_files(&[
+            (std::path::PathBuf::from("game/countries/GER/country.yaml"), "tag: GER\nname: Germany\n".to_string()),
+            (
+                std::path::PathBuf::from("game/countries/GER/focus_tree.yaml"),
+                "focuses:\n  - { id: a, name: A, days: 3 }\n  - { id: b, name: B, days: 2, prerequisites: [a] }\n".to_string(),
+            ),
+        ])
+    }
+
+    #[test]
+    fn live_reloads_reach_the_running_simulation() {
+        let mut sim = Simulation::new(content(), 1);
+        sim.submit(SimInput::StartFocus { country: "GER".into(), focus: "a".into() });
+        sim.run_until(StopWhen::Days(1));
+        sim.apply_live(&[LiveChange::FocusDays { country: "GER".into(), focus: "a".into(), old: 3, new: 6 }]);
+        assert_eq!(sim.content().focus_tree("GER").unwrap().find("a").unwrap().days, 6);
+        assert_eq!(sim.state.countries["GER"].focus.in_progress.as_ref().unwrap().remaining_days, 5);
+
+        let outcome = sim.run_until(StopWhen::Event { kind: SimEventKind::FocusCompleted, within_days: 10 });
+        assert_eq!(outcome.hours, 5 * 24);
+    }
+
+    #[test]
+    fn replays_match_whatever_the_frame_timing() {
+        let start = |country: &str, focus: &str| SimInput::StartFocus { country: country.into(), focus: focus.into() };
+
+        // Live run: uneven frames, inputs between them
+        let mut live = Simulation::new(content(), 7);
+        live.submit(start("GER", "a"));
+        for delta in [0.013, 0.4, 0.021, 0.9, 0.05, 0.6] {
+            live.advance(delta);
+        }
+        live.submit(start("GER", "b"));
+        live.advance(1.7);
+
+        let replayed = Simulation::replay(content(), live.input_log(), live.clock.elapsed_hours());
+        assert_eq!(replayed.clock.date(), live.clock.date());
+        let ger = |s: &Simulation| s.state.countries["GER"].focus.clone();
+        assert_eq!(ger(&replayed).completed, ger(&live).completed);
+        assert!(ger(&live).completed.contains("b"));
+        assert_eq!(replayed.input_log(), live.input_log());
+    }
+
+    #[test]
+    fn run_until_stops_at_dates_day_counts_and_events() {
+        let mut sim = Simulation::new(content(), 1);
+        sim.submit(SimInput::StartFocus { country: "GER".into(), focus: "a".into() });
+
+        let done = sim.run_until(StopWhen::Event { kind: SimEventKind::FocusCompleted, within_days: 30 });
+        assert_eq!(done.event, Some(SimEvent::FocusCompleted { country: "GER".into(), focus: "a".into() }));
+        assert_eq!(sim.clock.date().to_string(), "1936-01-04");
+        assert_eq!(sim.take_events().len(), 1);
+
+        // Nothing running, so the event wait gives up after its window
+        let idle = sim.run_until(StopWhen::Event { kind: SimEventKind::ResearchCompleted, within_days: 2 });
+        assert_eq!((idle.hours, idle.pulses.days, idle.event), (48, 2, None));
+
+        sim.run_until(StopWhen::Date("1936-02-01 06:00".parse().unwrap()));
+        assert_eq!(sim.clock.date().to_string(), "1936-02-01 06:00");
+        assert_eq!(sim.run_until(StopWhen::Days(10)).pulses.days, 10);
+        assert_eq!(sim.clock.date().to_string(), "1936-02-11 06:00");
+    }
+
+    #[test]
+    fn events_fire_for_ai_and_wait_for_players() {
+        let mut files = vec![
+            (std::path::PathBuf::from("game/countries/GER/country.yaml"), "tag: GER\nname: Germany\n".to_string()),
+            (std::path::PathBuf::from("game/countries/ITA/country.yaml"), "tag: ITA\nname: Italy\n".to_string()),
+        ];
+        files.push((
+            std::path::PathBuf::from("game/events/news.yaml"),
+            "events:\n  - id: e\n    title: E\n    date: 1936-01-03\n    options:\n      - { name: A, ai_weight: 0 }\n      - { name: B }\n".to_string(),
+        ));
+        let mut sim = Simulation::with_players(ContentDb::from_files(&files), 3, BTreeSet::from(["GER".to_string()]));
+
+        sim.run_until(StopWhen::Days(5));
+        let fired = sim.take_events();
+        assert_eq!(fired, vec![
+            SimEvent::EventFired { country: "GER".into(), event: "e".into(), option: None },
+            SimEvent::EventFired { country: "ITA".into(), event: "e".into(), option: Some(1) },
+        ]);
+        assert!(sim.pending_events("ITA").is_empty());
+        assert_eq!(sim.pending_events("GER")[0].fired.to_string(), "1936-01-03");
+
+        sim.submit(SimInput::ChooseEventOption { country: "GER".into(), event: "e".into(), option: 0 });
+        sim.run_until(StopWhen::Days(5));
+        assert!(sim.pending_events("GER").is_empty());
+        assert!(sim.take_events().is_empty());
+    }
+
+    #[test]
+    fn switching_players_hands_waiting_events_to_the_ai_and_replays() {
+        let files = vec![
+            (std::path::PathBuf::from("game/countries/GER/country.yaml"), "tag: GER\nname: Germany\n".to_string()),
+            (std::path::PathBuf::from("game/countries/ITA/country.yaml"), "tag: ITA\nname: Italy\n".to_string()),
+            (
+                std::path::PathBuf::from("game/events/news.yaml"),
+                "events:\n  - id: e\n    title: E\n    date: 1936-01-03\n    options:\n      - { name: A, effects: [{ type: set_flag, flag: chose_a }] }\n".to_string(),
+            ),
+        ];
+        let content = ContentDb::from_files(&files);
+        let mut sim = Simulation::with_players(content.clone(), 3, BTreeSet::from(["GER".to_string()]));
+        sim.run_until(StopWhen::Days(5));
+        assert_eq!(sim.pending_events("GER").len(), 1);
+
+        sim.set_players(BTreeSet::from(["ITA".to_string()]));
+        sim.run_until(StopWhen::Days(1));
+        assert!(sim.pending_events("GER").is_empty());
+        assert!(sim.state.countries["GER"].script.has_flag("chose_a"));
+        assert!(sim.state.players.contains("ITA"));
+
+        let replayed = Simulation::replay(content, sim.input_log(), sim.clock.elapsed_hours());
+        assert_eq!(replayed.state.players, sim.state.players);
+        assert!(replayed.state.countries["GER"].script.has_flag("chose_a"));
+    }
+
+    #[test]
+    fn completed_focuses_and_techs_apply_modifiers_that_queries_see() {
+        let files = vec![
+            (std::path::PathBuf::from("game/countries/GER/country.yaml"), "tag: GER\nname: Germany\nresources: { civ_factories: 10 }\n".to_string()),
+            (
+                std::path::PathBuf::from("game/countries/GER/focus_tree.yaml"),
+                "focuses:\n  - id: a\n    name: A\n    days: 1\n    effects:\n      - { type: add_civ_factories, amount: 2 }\n      - { type: add_modifier, key: research_speed, value: 1, days: 30 }\n".to_string(),
+            ),
+            (
+                std::path::PathBuf::from("game/research/technologies.yaml"),
+                "technologies:\n  - id: t\n    name: T\n    days: 10\n    effects: [{ type: unit_stat_mod, unit: infantry, soft_attack: 1 }]\n".to_string(),
+            ),
+            (
+                std::path::PathBuf::from("game/units/battalions.yaml"),
+                "battalions:\n  - { id: infantry, name: Infantry, stats: { soft_attack: 5, hp: 25 } }\n".to_string(),
+            ),
+        ];
+        let mut sim = Simulation::new(ContentDb::from_files(&files), 1);
+        sim.submit(SimInput::StartFocus { country: "GER".into(), focus: "a".into() });
+        sim.run_until(StopWhen::Days(1));
+        assert_eq!(sim.country_breakdown("GER", "civ_factories").value, 12.0);
+
+        // Research runs twice as fast while the focus bonus lasts
+        sim.submit(SimInput::StartResearch { country: "GER".into(), tech: "t".into() });
+        let done = sim.run_until(StopWhen::Event { kind: SimEventKind::ResearchCompleted, within_days: 20 });
+        assert_eq!(done.pulses.days, 5);
+        assert_eq!(sim.battalion_stats("GER", "infantry").map(|s| (s.soft_attack, s.hp)), Some((6.0, 25.0)));
+        let attack = sim.unit_stat_breakdown("GER", "infantry", "soft_attack").unwrap();
+        assert_eq!(attack.terms[0].source, ModifierSource::Tech("t".into()));
+        assert_eq!(sim.battalion_stats("ITA", "infantry").map(|s| s.soft_attack), Some(5.0));
+
+        sim.run_until(StopWhen::Days(30));
+        assert_eq!(sim.state.modifiers.iter().filter(|m| m.key == "research_speed").count(), 0);
+    }
+
+    #[test]
+    fn failing_effects_are_reported_and_the_rest_still_run() {
+        let files = vec![
+            (std::path::PathBuf::from("game/countries/GER/country.yaml"), "tag: GER\nname: Germany\n".to_string()),
+            (
+                std::path::PathBuf::from("game/countries/GER/focus_tree.yaml"),
+                "focuses:\n  - id: a\n    name: A\n    days: 1\n    effects:\n      - { type: add_idea, idea: missing }\n      - { type: unlock_battalion, battalion: engineers }\n".to_string(),
+            ),
+            (std::path::PathBuf::from("game/units/battalions.yaml"), "battalions:\n  - { id: engineers, name: Engineers }\n".to_string()),
+        ];
+        let mut sim = Simulation::new(ContentDb::from_files(&files), 1);
+        sim.submit(SimInput::StartFocus { country: "GER".into(), focus: "a".into() });
+        let outcome = sim.run_until(StopWhen::Event { kind: SimEventKind::EffectFailed, within_days: 2 });
+        assert_eq!(
+            outcome.event,
+            Some(SimEvent::EffectFailed { country: "GER".into(), effect: "add_idea".into(), error: "add_idea: unknown idea missing".into() })
+        );
+        assert!(sim.state.countries["GER"].battalions.contains("engineers"));
+    }
+
+    #[test]
+    fn province_modifiers_add_to_their_owner() {
+        let files = vec![
+            (
+                std::path::PathBuf::from("game/map/provinces.yaml"),
+                "provinces:\n  - { id: 0, name: A, pos: { x: 0, y: 0 } }\n  - { id: 1, name: B, pos: { x: 10, y: 0 } }\n".to_string(),
+            ),
+            (
+                std::path::PathBuf::from("game/countries/GER/country.yaml"),
+                "tag: GER\nname: Germany\nowned_provinces: [0]\nresources: { civ_factories: 10 }\n".to_string(),
+            ),
+            (std::path::PathBuf::from("game/countries/FRA/country.yaml"), "tag: FRA\nname: France\nowned_provinces: [1]\n".to_string()),
+            (
+                std::path::PathBuf::from("game/countries/GER/focus_tree.yaml"),
+                "focuses:\n  - id: a\n    name: A\n    days: 1\n    effects:\n      - { type: add_modifier, key: civ_factories, value: 2, province: 0 }\n      - { type: add_modifier, key: civ_factories, value: 0.5, stacking: multiply, province: 0 }\n      - { type: add_modifier, key: civ_factories, value: 1, province: 1 }\n".to_string(),
+            ),
+        ];
+        let mut sim = Simulation::new(ContentDb::from_files(&files), 1);
+        sim.submit(SimInput::StartFocus { country: "GER".into(), focus: "a".into() });
+        sim.run_until(StopWhen::Days(1));
+        assert_eq!(sim.province_breakdown(0, "civ_factories").value, 3.0);
+        assert_eq!(sim.province_breakdown(1, "civ_factories").value, 1.0);
+        // Province multipliers stay within the province
+        assert_eq!(sim.country_breakdown("GER", "civ_factories").value, 13.0);
+        assert_eq!(sim.country_breakdown("FRA", "civ_factories").value, 1.0);
+    }
+
+    #[test]
+    fn timed_ideas_expire_and_survive_a_save() {
+        let files = vec![
+            (std::path::PathBuf::from("game/countries/GER/country.yaml"), "tag: GER\nname: Germany\nresources: { manpower: 100 }\n".to_string()),
+            (
+                std::path::PathBuf::from("game/countries/GER/focus_tree.yaml"),
+                "focuses:\n  - id: a\n    name: A\n    days: 1\n    effects:\n      - { type: add_idea, idea: drive, days: 10 }\n      - { type: add_idea, idea: zeal }\n".to_string(),
+            ),
+            (
+                std::path::PathBuf::from("game/ideas/ideas.yaml"),
+                "ideas:\n  - { id: drive, name: Drive, modifiers: [{ key: manpower, value: 0.5, stacking: multiply }] }\n  - { id: zeal, name: Zeal, modifiers: [{ key: manpower, value: 10 }] }\n".to_string(),
+            ),
+        ];
+        let content = ContentDb::from_files(&files);
+        let mut sim = Simulation::new(content.clone(), 1);
+        sim.submit(SimInput::StartFocus { country: "GER".into(), focus: "a".into() });
+        sim.run_until(StopWhen::Days(1));
+        let ger = &sim.state.countries["GER"];
+        assert!(ger.has_idea("drive") && ger.has_idea("zeal"));
+        assert_eq!(ger.idea("drive").unwrap().expires.unwrap().to_string(), "1936-01-12");
+        assert_eq!(sim.country_breakdown("GER", "manpower").value, 165.0);
+
+        // A save restores both the ideas and the modifiers they grant
+        let saved = sim.state.ideas_save();
+        let mut loaded = Simulation::new(content.clone(), 1);
+        loaded.state.load_ideas(&saved, &content);
+        assert_eq!(loaded.state.ideas_save(), saved);
+        assert_eq!(loaded.country_breakdown("GER", "manpower").value, 165.0);
+
+        sim.run_until(StopWhen::Days(10));
+        assert!(!sim.state.countries["GER"].has_idea("drive"));
+        assert_eq!(sim.country_breakdown("GER", "manpower").value, 110.0);
+        assert!(sim.state.remove_idea("GER", "zeal"));
+        assert_eq!(sim.country_breakdown("GER", "manpower").value, 100.0);
+    }
+
+    #[test]
+    fn drifting_popularity_topples_governments_and_elections_replace_them() {
+        let country = |tag: &str, ideology: &str, popularity: &str| {
+            let yaml = format!("tag: {}\nname: {}\nideology: {}\npopularity: {}\n", tag, tag, ideology, popularity);
+            (std::path::PathBuf::from(format!("game/countries/{}/country.yaml", tag)), yaml)
+        };
+        let files = vec![
+            (
+                std::path::PathBuf::from("game/ideologies.yaml"),
+                "ideologies:\n  - { id: democratic, name: D, elections: true }\n  - { id: fascism, name: F }\n  - { id: communism, name: C }\nrules: { election_interval_days: 20 }\n".to_string(),
+            ),
+            country("GER", "fascism", "{ fascism: 70, communism: 30 }"),
+            country("FRA", "democratic", "{ democratic: 30, fascism: 50, communism: 20 }"),
+            (
+                std::path::PathBuf::from("game/countries/GER/focus_tree.yaml"),
+                "focuses:\n  - id: a\n    name: A\n    days: 1\n    effects: [{ type: add_modifier, key: communism_drift, value: 0.05 }]\n  - id: b\n    name: B\n    days: 1\n    effects: [{ type: set_ruling_party, ideology: democratic }]\n".to_string(),
+            ),
+        ];
+        let mut sim = Simulation::new(ContentDb::from_files(&files), 1);
+        assert_eq!(sim.state.countries["FRA"].politics.next_election.unwrap().to_string(), "1936-01-21");
+        sim.submit(SimInput::StartFocus { country: "GER".into(), focus: "a".into() });
+
+        let war = sim.run_until(StopWhen::Event { kind: SimEventKind::CivilWarStarted, within_days: 30 });
+        assert_eq!(war.event, Some(SimEvent::CivilWarStarted { country: "GER".into(), ideology: "communism".into() }));
+        sim.run_until(StopWhen::Event { kind: SimEventKind::GovernmentChanged, within_days: 30 });
+        let fallen = sim.take_events();
+        assert!(fallen.ends_with(&[
+            SimEvent::GovernmentChanged {
+                country: "GER".into(),
+                from: Some("fascism".into()),
+                to: "communism".into(),
+                cause: GovernmentChange::CivilWar,
+            },
+            SimEvent::CivilWarEnded { country: "GER".into(), ideology: "communism".into(), rebels_won: true },
+        ]));
+
+        // Democracies vote instead; France elects a government that holds no more elections
+        let vote = sim.run_until(StopWhen::Event { kind: SimEventKind::ElectionHeld, within_days: 30 });
+        assert_eq!(vote.event, Some(SimEvent::ElectionHeld { country: "FRA".into(), winner: "fascism".into() }));
+        assert_eq!(sim.state.countries["FRA"].politics.ruling.as_deref(), Some("fascism"));
+        assert_eq!(sim.state.countries["FRA"].politics.next_election, None);
+        sim.take_events();
+
+        sim.submit(SimInput::StartFocus { country: "GER".into(), focus: "b".into() });
+        sim.run_until(StopWhen::Days(1));
+        assert!(sim.take_events().contains(&SimEvent::GovernmentChanged {
+            country: "GER".into(),
+            from: Some("communism".into()),
+            to: "democratic".into(),
+            cause: GovernmentChange::Effect,
+        }));
+        let scope = sim.state.scope("GER", sim.clock.date()).unwrap();
+        assert!(crate::core::events::Trigger::RulingIdeology("democratic".into()).holds(&scope));
+        assert!(scope.country.politics.next_election.is_some());
+    }
+
+    #[test]
+    fn negative_drift_wears_down_a_government_with_all_the_support() {
+        let file = |path: &str, yaml: &str| (std::path::PathBuf::from(path), yaml.to_string());
+        let files = vec![
+            file("game/ideologies.yaml", "ideologies:\n  - { id: fascism, name: F }\n  - { id: communism, name: C }\n"),
+            file("game/countries/ITA/country.yaml", "tag: ITA\nname: Italy\nideology: fascism\n"),
+            file(
+                "game/countries/ITA/focus_tree.yaml",
+                "focuses:\n  - id: a\n    name: A\n    days: 1\n    effects: [{ type: add_modifier, key: fascism_drift, value: -0.1 }]\n",
+            ),
+        ];
+        let mut sim = Simulation::new(ContentDb::from_files(&files), 1);
+        assert_eq!(sim.state.countries["ITA"].politics.popularity("fascism"), 1.0);
+        sim.submit(SimInput::StartFocus { country: "ITA".into(), focus: "a".into() });
+        sim.run_until(StopWhen::Days(3));
+        let politics = &sim.state.countries["ITA"].politics;
+        // The drift runs on the day the focus completes too
+        assert!((politics.popularity("fascism") - 0.7).abs() < 1e-9);
+        assert!((politics.popularity("communism") - 0.3).abs() < 1e-9);
+    }
+
+    #[test]
+    fn leaders_follow_the_government_and_generals_lead_their_divisions() {
+        let file = |path: &str, yaml: &str| (std::path::PathBuf::from(path), yaml.to_string());
+        let files = vec![
+            file("game/ideologies.yaml", "ideologies:\n  - { id: fascism, name: F }\n  - { id: democratic, name: D, elections: true }\n"),
+            file(
+                "game/countries/GER/country.yaml",
+                "tag: GER\nname: Germany\nideology: fascism\nresources: { civ_factories: 10, political_power: 10, political_power_gain: 2 }\n",
+            ),
+            file("game/traits.yaml", "traits:\n  - { id: panzer, name: P, modifiers: [{ key: hard_attack, value: 0.1, stacking: multiply }] }\n"),
+            file(
+                "game/countries/GER/characters.yaml",
+                "characters:
+  - { id: f, name: F, role: leader, ideology: fascism, modifiers: [{ key: political_power_gain, value: 1 }] }
+  - { id: d, name: D, role: leader, ideology: democratic }
+  - { id: adv, name: A, role: advisor, cost: 20, modifiers: [{ key: civ_factories, value: 2 }] }
+  - { id: g, name: G, role: general, skill: 2, traits: [panzer] }
+",
+            ),
+            file("game/units/battalions.yaml", "battalions:\n  - { id: armor, name: Armor, stats: { soft_attack: 10, hard_attack: 10 } }\n"),
+            file(
+                "game/countries/GER/focus_tree.yaml",
+                "focuses:
+  - { id: a, name: A, days: 1, effects: [{ type: set_ruling_party, ideology: democratic }] }
+  - { id: b, name: B, days: 1, effects: [{ type: promote_character, character: g }, { type: remove_character, character: d }] }
+",
+            ),
+        ];
+        let mut sim = Simulation::new(ContentDb::from_files(&files), 1);
+        let ger = |sim: &Simulation| sim.state.countries["GER"].clone();
+        assert_eq!(ger(&sim).characters.leader.as_deref(), Some("f"));
+
+        // Advisors wait until the country can pay: 10 + 3 a day with the leader's bonus
+        sim.submit(SimInput::HireAdvisor { country: "GER".into(), advisor: "adv".into() });
+        sim.run_until(StopWhen::Days(4));
+        assert!(ger(&sim).characters.advisors.is_empty());
+        sim.submit(SimInput::HireAdvisor { country: "GER".into(), advisor: "adv".into() });
+        sim.run_until(StopWhen::Days(1));
+        assert!((ger(&sim).politics.political_power - 5.0).abs() < 1e-9);
+        assert_eq!(sim.country_breakdown("GER", "civ_factories").value, 12.0);
+
+        // Skill 2 is +10% attack and defense, the trait another +10% hard attack
+        sim.submit(SimInput::AssignGeneral { country: "GER".into(), general: "g".into(), divisions: vec![7] });
+        sim.run_until(StopWhen::Days(1));
+        let led = sim.division_stats("GER", 7, "armor").unwrap();
+        assert_eq!((led.soft_attack, led.hard_attack), (11.0, 12.0));
+        assert_eq!(sim.division_stats("GER", 8, "armor").unwrap().hard_attack, 10.0);
+        sim.take_events();
+
+        sim.submit(SimInput::StartFocus { country: "GER".into(), focus: "a".into() });
+        sim.run_until(StopWhen::Days(1));
+        assert!(sim.take_events().contains(&SimEvent::LeaderChanged { country: "GER".into(), leader: Some("d".into()) }));
+        assert_eq!(sim.country_breakdown("GER", "political_power_gain").value, 2.0);
+
+        // A promoted general leads whatever the government and keeps commanding
+        sim.submit(SimInput::StartFocus { country: "GER".into(), focus: "b".into() });
+        sim.run_until(StopWhen::Days(1));
+        assert_eq!(ger(&sim).characters.leader.as_deref(), Some("g"));
+        assert!(!ger(&sim).characters.roster.contains("d"));
+        assert_eq!(sim.division_stats("GER", 7, "armor").unwrap().hard_attack, 12.0);
+    }
 }
@@ -2,7 +2,12 @@
 #[cfg(feature = "bevy")]
 use bevy::prelude::*;
 
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, Schema, SchemaObject, StringValidation};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
 #[cfg_attr(feature = "bevy", derive(Resource))]
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
@@ -10,46 +15,371 @@ pub struct Clock {
     pub current_day: u32,
     pub paused: bool,
     pub speed_idx: usize,
+    /// Game hours owed to the simulation but not yet ticked
     pub acc: f32,
+    /// Scenario start date; `current_day` counts from here
+    #[serde(default)]
+    pub start_date: GameDate,
+    /// Hour of the current day, 0-23
+    #[serde(default)]
+    pub hour: u8,
+    /// Seconds per day for each speed setting, from `game/speeds.yaml`
+    #[serde(default)]
+    pub speeds: SpeedTable,
+    /// Fast-forward target: while set, the clock runs as fast as
+    /// [`MAX_CATCH_UP_HOURS`] allows, ignoring pause and speed, and clears it on arrival
+    #[serde(default)]
+    pub run_until: Option<GameDate>,
 }
 
-pub const SPEEDS: [f32; 5] = [0.5, 0.35, 0.25, 0.18, 0.12];
+/// Real seconds per in-game day for each speed setting, slowest first.
+/// Loaded from `game/speeds.yaml`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SpeedTable {
+    #[schemars(length(min = 1))]
+    pub speeds: Vec<f32>,
+}
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct Tick;
-impl Event for Tick {}
+impl SpeedTable {
+    /// Seconds per day at `speed_idx`; indices past the end use the fastest speed.
+    pub fn seconds_per_day(&self, speed_idx: usize) -> f32 {
+        self.speeds.get(speed_idx).or(self.speeds.last()).copied().unwrap_or(DEFAULT_SPEEDS[0])
+    }
 
-#[cfg(feature = "bevy")]
-pub fn tick_system(time: Res<Time>, mut clock: ResMut<Clock>, mut ev: EventWriter<Tick>) {
-    if clock.paused {
-        return;
+    pub fn len(&self) -> usize {
+        self.speeds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.speeds.is_empty()
+    }
+}
+
+impl Default for SpeedTable {
+    fn default() -> Self {
+        SpeedTable { speeds: DEFAULT_SPEEDS.to_vec() }
+    }
+}
+
+impl Clock {
+    /// A running clock at day 0 of a scenario starting on `start_date`.
+    pub fn starting(start_date: GameDate) -> Self {
+        Clock { start_date, ..Default::default() }
+    }
+
+    /// Current in-game date and hour.
+    pub fn date(&self) -> GameDate {
+        self.start_date.add_days(i64::from(self.current_day)).add_hours(i64::from(self.hour))
+    }
+
+    /// Hours simulated since the scenario started.
+    pub fn elapsed_hours(&self) -> u64 {
+        u64::from(self.current_day) * u64::from(HOURS_PER_DAY) + u64::from(self.hour)
+    }
+
+    /// Move forward one hour, reporting the pulses that fired.
+    pub fn advance_hour(&mut self) -> Pulses {
+        let mut pulses = Pulses { hours: 1, ..Default::default() };
+        self.hour += 1;
+        if u32::from(self.hour) == HOURS_PER_DAY {
+            self.hour = 0;
+            self.current_day = self.current_day.wrapping_add(1);
+            pulses.days = 1;
+            let date = self.date();
+            pulses.weeks = u32::from(date.weekday() == 0);
+            pulses.months = u32::from(date.day() == 1);
+        }
+        pulses
+    }
+}
+
+pub const HOURS_PER_DAY: u32 = 24;
+
+/// How many ticks of each cadence fired during one clock advance. A day
+/// pulse fires at each midnight, a week pulse at midnight on Monday and a
+/// month pulse at midnight on the 1st.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Pulses {
+    pub hours: u32,
+    pub days: u32,
+    pub weeks: u32,
+    pub months: u32,
+}
+
+impl std::ops::AddAssign for Pulses {
+    fn add_assign(&mut self, other: Pulses) {
+        self.hours += other.hours;
+        self.days += other.days;
+        self.weeks += other.weeks;
+        self.months += other.months;
+    }
+}
+
+/// A date and hour on the proleptic Gregorian calendar. Written as
+/// `1936-01-01`, or `1936-01-01 14:00` when the hour is not midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct GameDate {
+    // Field order gives chronological ordering
+    year: i32,
+    month: u8,
+    day: u8,
+    hour: u8,
+}
+
+impl GameDate {
+    /// Start date used when a scenario does not set one.
+    pub const DEFAULT_START: GameDate = GameDate { year: 1936, month: 1, day: 1, hour: 0 };
+
+    /// Midnight on the given day, if it exists.
+    pub fn new(year: i32, month: u8, day: u8) -> Option<Self> {
+        let valid = (1..=12).contains(&month) && day >= 1 && day <= days_in_month(year, month);
+        valid.then_some(GameDate { year, month, day, hour: 0 })
+    }
+
+    pub fn with_hour(self, hour: u8) -> Option<Self> {
+        (hour < 24).then_some(GameDate { hour, ..self })
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
     }
-    clock.acc += time.delta_seconds();
-    let spd = SPEEDS[clock.speed_idx.min(4)];
-    while clock.acc >= spd {
-        clock.acc -= spd;
-        clock.current_day = clock.current_day.wrapping_add(1);
-        ev.send(Tick);
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    /// The same hour `days` days later (or earlier, if negative).
+    pub fn add_days(self, days: i64) -> Self {
+        let (year, month, day) = civil_from_days(self.day_number() + days);
+        GameDate { year, month, day, hour: self.hour }
+    }
+
+    pub fn add_hours(self, hours: i64) -> Self {
+        let total = i64::from(self.hour) + hours;
+        let date = self.add_days(total.div_euclid(24));
+        GameDate { hour: total.rem_euclid(24) as u8, ..date }
+    }
+
+    /// Day of the week, 0 = Monday.
+    pub fn weekday(&self) -> u8 {
+        // 1970-01-01 was a Thursday
+        (self.day_number() + 3).rem_euclid(7) as u8
+    }
+
+    /// Hours from `earlier` to `self`.
+    pub fn hours_since(&self, earlier: GameDate) -> i64 {
+        self.days_since(earlier) * i64::from(HOURS_PER_DAY) + i64::from(self.hour) - i64::from(earlier.hour)
+    }
+
+    /// Whole days from `earlier` to `self`, ignoring hours.
+    pub fn days_since(&self, earlier: GameDate) -> i64 {
+        self.day_number() - earlier.day_number()
+    }
+
+    /// Days since 1970-01-01.
+    fn day_number(&self) -> i64 {
+        days_from_civil(self.year, self.month, self.day)
     }
 }
 
-/// Advance the clock by delta seconds and return how many ticks occurred.
-/// This is the non-Bevy testable core of `tick_system`.
-pub fn advance_clock(clock: &mut Clock, delta_seconds: f32) -> u32 {
-    if clock.paused {
-        return 0;
+impl Default for GameDate {
+    fn default() -> Self {
+        GameDate::DEFAULT_START
     }
-    clock.acc += delta_seconds;
-    let spd = SPEEDS[clock.speed_idx.min(4)];
-    let mut ticks = 0u32;
-    while clock.acc >= spd {
-        clock.acc -= spd;
-        clock.current_day = clock.current_day.wrapping_add(1);
-        ticks += 1;
+}
+
+pub fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+pub fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
     }
-    ticks
 }
 
+// Constant-time conversions between dates and day numbers, after Howard
+// Hinnant's `days_from_civil` / `civil_from_days`. Years run March to
+// February internally so the leap day falls at the end.
+fn days_from_civil(year: i32, month: u8, day: u8) -> i64 {
+    let y = i64::from(year) - i64::from(month <= 2);
+    let era = y.div_euclid(400);
+    let year_of_era = y - era * 400;
+    let month_from_march = (i64::from(month) + 9) % 12;
+    let day_of_year = (153 * month_from_march + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i32, u8, u8) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u8;
+    let month = if month_from_march < 10 { month_from_march + 3 } else { month_from_march - 9 } as u8;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year as i32, month, day)
+}
+
+impl fmt::Display for GameDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)?;
+        if self.hour != 0 {
+            write!(f, " {:02}:00", self.hour)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for GameDate {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (date, hour) = match s.trim().split_once(' ') {
+            Some((date, time)) => {
+                let hour = time.strip_suffix(":00").unwrap_or(time);
+                (date, hour.parse::<u8>().map_err(|_| anyhow::anyhow!("invalid hour in date {:?}", s))?)
+            }
+            None => (s.trim(), 0),
+        };
+        let parts: Vec<&str> = date.split('-').collect();
+        let [year, month, day] = parts.as_slice() else {
+            anyhow::bail!("date {:?} is not YYYY-MM-DD", s);
+        };
+        let parse = |v: &str| v.parse::<u32>().map_err(|_| anyhow::anyhow!("date {:?} is not YYYY-MM-DD", s));
+        let (year, month, day) = (parse(year)?, parse(month)?, parse(day)?);
+        GameDate::new(year as i32, month.try_into()?, day.try_into()?)
+            .and_then(|d| d.with_hour(hour))
+            .ok_or_else(|| anyhow::anyhow!("{:?} is not a calendar date", s))
+    }
+}
+
+impl TryFrom<String> for GameDate {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<GameDate> for String {
+    fn from(date: GameDate) -> Self {
+        date.to_string()
+    }
+}
+
+impl JsonSchema for GameDate {
+    fn schema_name() -> String {
+        "GameDate".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            string: Some(Box::new(StringValidation {
+                pattern: Some(r"^\d{4}-\d{2}-\d{2}( \d{2}:00)?$".to_string()),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// Speed table used when `game/speeds.yaml` is absent.
+pub const DEFAULT_SPEEDS: [f32; 5] = [0.5, 0.35, 0.25, 0.18, 0.12];
+
+/// One in-game hour passed. Combat and movement run on this.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Tick;
+impl Event for Tick {}
+
+/// Midnight: a new in-game day started.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DayTick;
+impl Event for DayTick {}
+
+/// Midnight on a Monday.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct WeekTick;
+impl Event for WeekTick {}
+
+/// Midnight on the 1st of a month.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct MonthTick;
+impl Event for MonthTick {}
+
+#[cfg(feature = "bevy")]
+pub fn tick_system(
+    time: Res<Time>,
+    mut clock: ResMut<Clock>,
+    mut hours: EventWriter<Tick>,
+    mut days: EventWriter<DayTick>,
+    mut weeks: EventWriter<WeekTick>,
+    mut months: EventWriter<MonthTick>,
+) {
+    let pulses = advance_clock(&mut clock, time.delta_seconds());
+    hours.send_batch((0..pulses.hours).map(|_| Tick));
+    days.send_batch((0..pulses.days).map(|_| DayTick));
+    weeks.send_batch((0..pulses.weeks).map(|_| WeekTick));
+    months.send_batch((0..pulses.months).map(|_| MonthTick));
+}
+
+/// Most game hours one frame may simulate. Time owed beyond this after a
+/// hitch is dropped rather than carried over, so a slow frame cannot snowball
+/// into ever longer catch-up frames.
+pub const MAX_CATCH_UP_HOURS: u32 = 96;
+
+/// Advance the clock by delta seconds, one hour at a time, and report which
+/// pulses fired. This is the non-Bevy testable core of `tick_system`.
+pub fn advance_clock(clock: &mut Clock, delta_seconds: f32) -> Pulses {
+    advance_clock_with(clock, delta_seconds, |_, _| {})
+}
+
+/// [`advance_clock`], calling `on_hour` after every hour step with the clock
+/// and the pulses of that hour. Real time only decides how many fixed steps
+/// run; the steps themselves never see frame timing.
+pub fn advance_clock_with(clock: &mut Clock, delta_seconds: f32, mut on_hour: impl FnMut(&Clock, Pulses)) -> Pulses {
+    let mut pulses = Pulses::default();
+    let due = if let Some(target) = clock.run_until {
+        let remaining = target.hours_since(clock.date()).max(0) as u32;
+        if remaining <= MAX_CATCH_UP_HOURS {
+            clock.run_until = None;
+        }
+        clock.acc = 0.0;
+        remaining.min(MAX_CATCH_UP_HOURS)
+    } else {
+        if clock.paused {
+            return pulses;
+        }
+        let spd = clock.speeds.seconds_per_day(clock.speed_idx);
+        clock.acc += delta_seconds * HOURS_PER_DAY as f32 / spd;
+        let due = (clock.acc as u32).min(MAX_CATCH_UP_HOURS);
+        clock.acc = if due == MAX_CATCH_UP_HOURS { clock.acc.fract() } else { clock.acc - due as f32 };
+        due
+    };
+    for _ in 0..due {
+        let hour = clock.advance_hour();
+        on_hour(clock, hour);
+        pulses += hour;
+    }
+    pulses
+}
 
 #[cfg(test)]
 mod tests {
@@ -57,38 +387,89 @@ mod tests {
 
     #[test]
     fn advance_clock_paused() {
-        let mut c = Clock { current_day: 0, paused: true, speed_idx: 0, acc: 0.0 };
+        let mut c = Clock { current_day: 0, paused: true, speed_idx: 0, acc: 0.0, ..Default::default() };
         let t = advance_clock(&mut c, 1.0);
-        assert_eq!(t, 0);
+        assert_eq!(t, Pulses::default());
         assert_eq!(c.current_day, 0);
         assert_eq!(c.acc, 0.0);
     }
 
     #[test]
     fn advance_clock_partial_accumulation() {
-        let mut c = Clock { current_day: 0, paused: false, speed_idx: 0, acc: 0.0 };
+        let mut c = Clock { current_day: 0, paused: false, speed_idx: 0, acc: 0.0, ..Default::default() };
+        // 0.2s of a 0.5s day is 9.6 hours
         let t = advance_clock(&mut c, 0.2);
-        assert_eq!(t, 0);
-        assert!(c.acc > 0.19 && c.acc < 0.21);
+        assert_eq!((t.hours, t.days), (9, 0));
+        assert_eq!(c.hour, 9);
+        assert!(c.acc > 0.59 && c.acc < 0.61);
     }
 
     #[test]
     fn advance_clock_multiple_ticks() {
-        let mut c = Clock { current_day: 10, paused: false, speed_idx: 0, acc: 0.0 };
-        // speed 0 => 0.5s per day, so 1.5s => 3 ticks
+        let mut c = Clock { current_day: 10, paused: false, speed_idx: 0, acc: 0.0, ..Default::default() };
+        // speed 0 => 0.5s per day, so 1.5s => 3 days of hourly ticks
         let t = advance_clock(&mut c, 1.5);
-        assert_eq!(t, 3);
+        assert_eq!((t.hours, t.days), (72, 3));
         assert_eq!(c.current_day, 13);
-        assert!(c.acc < 0.5);
+        assert!(c.acc < 1.0);
     }
 
     #[test]
     fn advance_clock_speed_index() {
-        let mut c = Clock { current_day: 0, paused: false, speed_idx: 2, acc: 0.0 };
+        let mut c = Clock { current_day: 0, paused: false, speed_idx: 2, acc: 0.0, ..Default::default() };
         // speed_idx 2 -> 0.25s per day
         let t = advance_clock(&mut c, 0.5);
-        assert_eq!(t, 2);
+        assert_eq!(t.days, 2);
         assert_eq!(c.current_day, 2);
     }
-}
 
+    #[test]
+    fn advance_clock_caps_catch_up_after_a_hitch() {
+        let mut c = Clock { speed_idx: 4, ..Default::default() };
+        // A 2s stall at 0.12s per day would owe 400 hours
+        let t = advance_clock(&mut c, 2.0);
+        assert_eq!(t.hours, MAX_CATCH_UP_HOURS);
+        assert!(c.acc < 1.0);
+        assert_eq!(advance_clock(&mut c, 0.0).hours, 0);
+    }
+
+    #[test]
+    fn speeds_come_from_the_table_and_run_until_fast_forwards() {
+        let mut c = Clock { speed_idx: 7, speeds: SpeedTable { speeds: vec![2.0, 1.0] }, ..Default::default() };
+        // Out-of-range index uses the fastest entry: 1s per day
+        assert_eq!(advance_clock(&mut c, 0.5).hours, 12);
+
+        c.paused = true;
+        c.run_until = Some("1936-01-06".parse().unwrap());
+        assert_eq!(advance_clock(&mut c, 0.0).hours, MAX_CATCH_UP_HOURS);
+        assert_eq!(advance_clock(&mut c, 0.0).hours, 4 * 24 + 12 - MAX_CATCH_UP_HOURS);
+        assert_eq!(c.date().to_string(), "1936-01-06");
+        assert_eq!(c.run_until, None);
+        assert_eq!(advance_clock(&mut c, 1.0).hours, 0);
+    }
+
+    #[test]
+    fn advance_clock_reports_week_and_month_pulses() {
+        // Friday 1936-01-31: midnight brings February, then Monday comes on the third day
+        let mut c = Clock::starting("1936-01-31".parse().unwrap());
+        let t = advance_clock(&mut c, 1.5);
+        assert_eq!(t, Pulses { hours: 72, days: 3, weeks: 1, months: 1 });
+        assert_eq!(c.date().to_string(), "1936-02-03");
+    }
+
+    #[test]
+    fn game_date_follows_the_gregorian_calendar() {
+        let date = |s: &str| s.parse::<GameDate>().unwrap();
+        assert_eq!(date("1936-02-28").add_days(1), date("1936-02-29"));
+        assert_eq!(date("1937-02-28").add_days(1), date("1937-03-01"));
+        assert_eq!(date("1936-01-01").add_days(366), date("1937-01-01"));
+        assert_eq!(date("1999-12-31 23:00").add_hours(2), date("2000-01-01 01:00"));
+        assert_eq!(date("1948-06-15").days_since(date("1936-01-01")), 4549);
+        assert_eq!(date("1936-03-01 14:00").to_string(), "1936-03-01 14:00");
+        assert!("1900-02-29".parse::<GameDate>().is_err());
+        assert!("2000-02-29".parse::<GameDate>().is_ok());
+
+        let clock = Clock { current_day: 60, ..Clock::starting(date("1939-09-01")) };
+        assert_eq!(clock.date(), date("1939-10-31"));
+    }
+}
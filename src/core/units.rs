@@ -1,10 +1,63 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Combat stats of a battalion; omitted stats default to zero.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BattalionStats {
+    #[serde(default)]
+    pub soft_attack: f32,
+    #[serde(default)]
+    pub hard_attack: f32,
+    #[serde(default)]
+    pub defense: f32,
+    #[serde(default)]
+    pub hp: f32,
+    #[serde(default)]
+    pub speed: f32,
+}
+
+impl BattalionStats {
+    /// Stat names as written in content, e.g. in `unit_stat_mod` effects.
+    pub const NAMES: [&'static str; 5] = ["soft_attack", "hard_attack", "defense", "hp", "speed"];
+
+    pub fn get(&self, name: &str) -> Option<f32> {
+        match name {
+            "soft_attack" => Some(self.soft_attack),
+            "hard_attack" => Some(self.hard_attack),
+            "defense" => Some(self.defense),
+            "hp" => Some(self.hp),
+            "speed" => Some(self.speed),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut f32> {
+        match name {
+            "soft_attack" => Some(&mut self.soft_attack),
+            "hard_attack" => Some(&mut self.hard_attack),
+            "defense" => Some(&mut self.defense),
+            "hp" => Some(&mut self.hp),
+            "speed" => Some(&mut self.speed),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct BattalionDef {
+    /// Unique battalion identifier
     pub id: String,
+    /// Display name of the battalion
     pub name: String,
+    #[serde(default)]
+    pub stats: BattalionStats,
+    /// Relative weight of the battalion within a division
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f32>,
+    /// Grouping such as `support`; line battalions leave it out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Battalions { pub battalions: Vec<BattalionDef> }
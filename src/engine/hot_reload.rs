@@ -0,0 +1,73 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use std::path::PathBuf;
+
+use crate::content::{
+    db::ContentDb,
+    pipeline::ContentReport,
+    reload::{reload_into, ReloadOutcome},
+    watch::ContentWatcher,
+};
+use crate::core::sim::Simulation;
+
+/// Watcher over the content root the game was started from.
+#[derive(Resource)]
+pub struct HotReload {
+    pub root: PathBuf,
+    watcher: ContentWatcher,
+}
+
+/// Report from the last rejected reload, shown until content validates again.
+#[derive(Resource, Default)]
+pub struct ContentErrors(pub Option<ContentReport>);
+
+pub fn setup_hot_reload(mut commands: Commands) {
+    let root = PathBuf::from(".");
+    match ContentWatcher::new(&root) {
+        Ok(watcher) => commands.insert_resource(HotReload { root, watcher }),
+        Err(e) => warn!("Content hot reload disabled: {:#}", e),
+    }
+}
+
+pub fn hot_reload_system(
+    hot: Option<Res<HotReload>>,
+    mut content: ResMut<ContentDb>,
+    mut errors: ResMut<ContentErrors>,
+    mut sim: Option<ResMut<Simulation>>,
+) {
+    let Some(hot) = hot else { return };
+    if !hot.watcher.poll_changed() {
+        return;
+    }
+    match reload_into(&hot.root, &mut content) {
+        ReloadOutcome::Applied(diff) => {
+            errors.0 = None;
+            for change in &diff.live {
+                info!("Hot reload: {}", change);
+            }
+            if let Some(sim) = sim.as_mut() {
+                sim.apply_live(&diff.live);
+            }
+            if !diff.needs_restart.is_empty() {
+                warn!("Hot reload: changes to {} take effect after a restart", diff.needs_restart.join(", "));
+            }
+        }
+        ReloadOutcome::Rejected(report) => {
+            warn!("{}", report);
+            errors.0 = Some(report);
+        }
+    }
+}
+
+pub fn content_errors_ui(mut contexts: EguiContexts, errors: Res<ContentErrors>) {
+    let Some(report) = &errors.0 else { return };
+    egui::Window::new("Content errors").show(contexts.ctx_mut(), |ui| {
+        ui.label("The last content change failed validation; the game keeps using the previous content.");
+        ui.separator();
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for e in &report.errors {
+                ui.label(e);
+            }
+        });
+    });
+}
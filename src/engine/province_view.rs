@@ -1,9 +1,8 @@
 use bevy::prelude::*;
 use bevy_prototype_lyon::prelude::*;
+use crate::content::db::ContentDb;
 use crate::core::{
     components::*,
-    province::ProvinceDef,
-    country::CountryDef,
     types::CountryTag,
 };
 
@@ -12,23 +11,16 @@ const HOVER_OUTLINE_WIDTH: f32 = 2.0;
 
 pub fn spawn_province_markers(
     commands: &mut Commands,
-    provinces: &[ProvinceDef],
-    countries: &[CountryDef],
+    content: &ContentDb,
 ) {
-    for province in provinces.iter() {
+    for province in content.provinces.values() {
         let pos = &province.pos;
-        // Find owning country (first whose owned_provinces contains id)
-        let mut owner_tag: Option<CountryTag> = None;
-        let mut owner_color: Color = Color::GRAY;
-        for c in countries {
-            if let Some(owned) = &c.owned_provinces {
-                if owned.iter().any(|pid| *pid == province.id) {
-                    if let Ok(tag) = c.tag.parse() { owner_tag = Some(tag); }
-                    if let Some(col) = &c.color { owner_color = Color::rgb(col.r, col.g, col.b); }
-                    break;
-                }
-            }
-        }
+        let owner = content.owner_of(province.id);
+        let owner_tag: Option<CountryTag> = owner.and_then(|c| c.tag.parse().ok());
+        let owner_color = owner
+            .and_then(|c| c.color.as_ref())
+            .map(|col| Color::rgb(col.r, col.g, col.b))
+            .unwrap_or(Color::GRAY);
         let owner_tag = owner_tag.unwrap_or_else(|| "ZZZ".parse().unwrap());
         
         // Create circle shape
@@ -0,0 +1,33 @@
+use bevy_egui::{egui, EguiContexts};
+use bevy::prelude::*;
+use crate::core::sim::{SimInput, Simulation};
+use crate::engine::PlayerCountry;
+
+/// Show the oldest event waiting for the player's country and submit the
+/// option they pick.
+pub fn ui_events_system(
+    mut contexts: EguiContexts<'_, '_>,
+    sim: Option<ResMut<Simulation>>,
+    player: Res<PlayerCountry>,
+) {
+    let (Some(mut sim), Some(tag)) = (sim, player.0.clone()) else { return };
+    let Some(pending) = sim.pending_events(&tag).first().cloned() else { return };
+    let Some(def) = sim.content().event(&pending.event).cloned() else { return };
+
+    let mut chosen = None;
+    egui::Window::new(&def.title).collapsible(false).show(contexts.ctx_mut(), |ui| {
+        ui.label(format!("{}", pending.fired));
+        if !def.description.is_empty() {
+            ui.label(&def.description);
+        }
+        ui.separator();
+        for (i, option) in def.options.iter().enumerate() {
+            if ui.button(&option.name).clicked() {
+                chosen = Some(i);
+            }
+        }
+    });
+    if let Some(option) = chosen {
+        sim.submit(SimInput::ChooseEventOption { country: tag, event: pending.event, option });
+    }
+}
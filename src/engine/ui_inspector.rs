@@ -0,0 +1,62 @@
+use bevy_egui::{egui, EguiContexts};
+use bevy::prelude::*;
+use crate::core::script::ScriptState;
+use crate::core::sim::Simulation;
+use crate::engine::PlayerCountry;
+
+/// Whether the script debug inspector is shown; F3 toggles it.
+#[derive(Resource, Default)]
+pub struct ScriptInspector {
+    pub open: bool,
+}
+
+/// List the flags and variables of the game and the player's country, for
+/// modders debugging event chains.
+pub fn ui_inspector_system(
+    mut contexts: EguiContexts<'_, '_>,
+    keyboard: Res<Input<KeyCode>>,
+    mut inspector: ResMut<ScriptInspector>,
+    sim: Option<Res<Simulation>>,
+    player: Res<PlayerCountry>,
+) {
+    if keyboard.just_pressed(KeyCode::F3) {
+        inspector.open = !inspector.open;
+    }
+    let Some(sim) = sim else { return };
+    if !inspector.open {
+        return;
+    }
+    egui::Window::new("Script state").open(&mut inspector.open).show(contexts.ctx_mut(), |ui| {
+        ui.heading("Global");
+        script_grid(ui, "global_script", &sim.state.global);
+        if let Some(tag) = &player.0 {
+            ui.separator();
+            ui.heading(tag);
+            match sim.state.countries.get(tag) {
+                Some(country) => script_grid(ui, "country_script", &country.script),
+                None => {
+                    ui.label("Not in the simulation");
+                }
+            }
+        }
+    });
+}
+
+fn script_grid(ui: &mut egui::Ui, id: &str, script: &ScriptState) {
+    if script.is_empty() {
+        ui.label("No flags or variables");
+        return;
+    }
+    egui::Grid::new(id).striped(true).show(ui, |ui| {
+        for flag in &script.flags {
+            ui.label("flag");
+            ui.label(flag);
+            ui.end_row();
+        }
+        for (name, value) in &script.variables {
+            ui.label("variable");
+            ui.label(format!("{} = {}", name, value));
+            ui.end_row();
+        }
+    });
+}
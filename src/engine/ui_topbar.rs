@@ -1,34 +1,16 @@
 use bevy_egui::{egui, EguiContexts};
 use bevy::prelude::*;
+use std::collections::BTreeSet;
 use crate::core::time::Clock;
-use crate::engine::{LoadedCountries, PlayerCountry};
-
-fn format_date_from_day(day0: u32) -> String {
-    // Assume epoch 1936-01-01 and 30-day months for a simple UI stub
-    let base_year = 1936u32;
-    let mut days = day0;
-    let mut year = base_year;
-    let mut month = 1u32;
-    let mut day = 1u32;
-    while days > 0 {
-        day += 1;
-        if day > 30 {
-            day = 1;
-            month += 1;
-            if month > 12 {
-                month = 1;
-                year += 1;
-            }
-        }
-        days -= 1;
-    }
-    format!("{:04}-{:02}-{:02}", year, month, day)
-}
+use crate::core::sim::Simulation;
+use crate::content::db::ContentDb;
+use crate::engine::PlayerCountry;
 
 pub fn ui_topbar_system(
     mut contexts: EguiContexts<'_, '_>,
     mut clock: ResMut<Clock>,
-    countries: Option<Res<LoadedCountries>>,
+    content: Option<Res<ContentDb>>,
+    mut sim: Option<ResMut<Simulation>>,
     mut player: ResMut<PlayerCountry>,
 ) {
     egui::TopBottomPanel::top("top_panel").show(contexts.ctx_mut(), |ui| {
@@ -38,29 +20,50 @@ pub fn ui_topbar_system(
             }
             
             ui.label("Speed: ");
-            for i in 1..=5 {
-                if ui.button(format!("{}×", i)).clicked() {
-                    clock.speed_idx = (i - 1) as usize;
+            for i in 0..clock.speeds.len() {
+                if ui.button(format!("{}×", i + 1)).clicked() {
+                    clock.speed_idx = i;
                 }
             }
+            // Fast-forward a month as quickly as the simulation can go
+            if clock.run_until.is_some() {
+                if ui.button("⏹").clicked() {
+                    clock.run_until = None;
+                }
+            } else if ui.button("⏭ 30d").clicked() {
+                clock.run_until = Some(clock.date().add_days(30));
+            }
             
-            ui.label(format!("Date: {}", format_date_from_day(clock.current_day)));
+            ui.label(format!("Date: {}", clock.date()));
 
-            if let Some(cres) = &countries {
+            if let Some(db) = &content {
                 ui.separator();
                 ui.label("Country:");
                 egui::ComboBox::from_id_source("country_select").selected_text(
                     player.0.clone().unwrap_or_else(|| "None".into())
                 ).show_ui(ui, |ui| {
-                    for c in &cres.0 {
+                    for c in db.countries.values() {
                         let tag = &c.tag;
                         if ui.selectable_label(player.0.as_ref() == Some(tag), tag).clicked() {
                             player.0 = Some(tag.clone());
+                            if let Some(sim) = sim.as_mut() {
+                                sim.set_players(BTreeSet::from([tag.clone()]));
+                            }
                         }
                     }
                 });
-                if let Some(active) = &player.0 {
-                    if let Some(cdef) = cres.0.iter().find(|c| &c.tag == active) {
+                if let (Some(active), Some(sim)) = (&player.0, &sim) {
+                    // Effective values; hover for where they came from
+                    for (label, key) in [("Manpower", "manpower"), ("Civ", "civ_factories"), ("Mil", "mil_factories")] {
+                        let breakdown = sim.country_breakdown(active, key);
+                        ui.label(format!("{}: {}", label, breakdown.value.round())).on_hover_text(breakdown.to_string());
+                    }
+                    if let Some(country) = sim.state.countries.get(active) {
+                        let gain = sim.country_breakdown(active, "political_power_gain");
+                        ui.label(format!("PP: {}", country.politics.political_power.floor())).on_hover_text(gain.to_string());
+                    }
+                } else if let Some(active) = &player.0 {
+                    if let Some(cdef) = db.country(active) {
                         ui.label(format!("Manpower: {}", cdef.resources.as_ref().and_then(|r| r.get("manpower")).and_then(|m| m.as_u64()).unwrap_or(0)));
                         ui.label(format!("Civ: {} Mil: {}", 
                             cdef.resources.as_ref().and_then(|r| r.get("civ_factories")).and_then(|v| v.as_u64()).unwrap_or(0),
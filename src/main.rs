@@ -5,8 +5,10 @@ use bevy::prelude::*;
 
 #[cfg(feature = "bevy")]
 use panopticon::{
+    content::pipeline::load_validated,
     core::{
-        time::{tick_system, Clock, Tick},
+        time::{tick_system, Clock, DayTick, MonthTick, Tick, WeekTick},
+        sim::Simulation,
         effects::setup_effect_registry,
         simulation::SimulationPlugin,
     },
@@ -15,7 +17,24 @@ use panopticon::{
 
 #[cfg(feature = "bevy")]
 fn main() -> Result<()> {
-    // Minimal Bevy app: inserts Clock resource and Tick event
+    // Load game + mods through the same pipeline as `validate`; broken content
+    // blocks startup instead of running with a half-loaded world.
+    let content = match load_validated(std::path::Path::new(".")) {
+        Ok(db) => db,
+        Err(report) => {
+            eprintln!("{}", report);
+            anyhow::bail!("game content is invalid; fix the errors above or run `cargo run --bin validate`");
+        }
+    };
+
+    let clock = Clock { speeds: content.speed_table(), ..Clock::starting(content.start_date()) };
+    // Logged at startup so a session can be reproduced with `Simulation::replay`
+    let seed: u64 = rand::random();
+    // The first country is the player's until they pick another in the top bar
+    let players = content.countries.keys().take(1).cloned().collect();
+    let sim = Simulation::with_players(content.clone(), seed, players);
+
+    // Minimal Bevy app: inserts Clock resource and the tick events for each cadence
     App::new()
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
@@ -26,10 +45,15 @@ fn main() -> Result<()> {
             }),
             ..default()
         }))
+        .insert_resource(content)
         .add_plugins((EnginePlugin, SimulationPlugin))
-        .insert_resource(Clock { current_day: 0, paused: false, speed_idx: 0, acc: 0.0 })
+        .insert_resource(clock)
+        .insert_resource(sim)
         .add_event::<Tick>()
-        .add_systems(Startup, setup_effect_registry)
+        .add_event::<DayTick>()
+        .add_event::<WeekTick>()
+        .add_event::<MonthTick>()
+        .add_systems(Startup, (setup_effect_registry, move || info!("Simulation seed: {}", seed)))
         .add_systems(Update, tick_system)
         .run();
 
@@ -44,8 +68,8 @@ fn main() -> Result<()> {
 }
 
 #[cfg(feature = "bevy")]
-fn print_tick(mut ev: EventReader<Tick>, clock: Res<Clock>) {
+fn print_tick(mut ev: EventReader<DayTick>, clock: Res<Clock>) {
     for _ in ev.iter() {
-        info!("Tick: day={}", clock.current_day);
+        info!("Tick: day={} date={}", clock.current_day, clock.date());
     }
 }
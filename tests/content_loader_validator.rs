@@ -11,3 +11,159 @@ fn validate_runs_and_succeeds() -> Result<(), Box<dyn std::error::Error>> {
         .stdout(predicate::str::contains("Validation complete"));
     Ok(())
 }
+
+// Integration test: --conflicts lists a file two mods override
+#[test]
+fn validate_conflicts_mode_reports_overrides() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("game/units"))?;
+    std::fs::write(root.join("game/units/battalions.yaml"), "battalions: []\n")?;
+    for id in ["alpha", "beta"] {
+        let units = root.join("mods").join(id).join("game/units");
+        std::fs::create_dir_all(&units)?;
+        std::fs::write(units.join("battalions.yaml"), format!("battalions:\n  - {{ id: {id}, name: {id} }}\n"))?;
+    }
+
+    let mut cmd = Command::cargo_bin("validate")?;
+    cmd.arg(root).arg("--conflicts");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("game/units/battalions.yaml: mod 'beta' overrides game, mod 'alpha'"));
+    Ok(())
+}
+
+// Integration test: --format json emits coded diagnostics and fails on errors
+#[test]
+fn validate_json_format_reports_coded_diagnostics() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("game/map"))?;
+    std::fs::write(root.join("game/map/provinces.yaml"), "provinces:\n  - { id: 0, name: A, pos: { x: 0, y: 0 } }\n")?;
+    std::fs::write(root.join("game/map/adjacency.yaml"), "edges:\n  - { a: 0, b: 3 }\n")?;
+
+    let mut cmd = Command::cargo_bin("validate")?;
+    cmd.arg(root).args(["--format", "json"]);
+    let output = cmd.assert().failure().get_output().stdout.clone();
+    let doc: serde_json::Value = serde_json::from_slice(&output)?;
+    let diag = &doc["diagnostics"][0];
+    assert_eq!(diag["code"], "PAN020");
+    assert_eq!(diag["severity"], "error");
+    assert_eq!(diag["location"]["path"], "game/map/adjacency.yaml");
+    Ok(())
+}
+
+// Integration test: --require-schemas fails on files the registry does not cover
+#[test]
+fn validate_require_schemas_reports_uncovered_files() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("game/map"))?;
+    std::fs::create_dir_all(root.join("schemas"))?;
+    std::fs::write(root.join("game/map/provinces.yaml"), "provinces:\n  - { id: 0, name: A, pos: { x: 0, y: 0 }, unowned: true }\n")?;
+    std::fs::write(root.join("game/map/adjacency.yaml"), "edges: []\n")?;
+    std::fs::write(root.join("schemas/registry.yaml"), "schemas:\n  - { pattern: \"game/map/provinces.yaml\", schema: provinces.schema.json }\n")?;
+    std::fs::write(root.join("schemas/provinces.schema.json"), r#"{ "type": "object", "required": ["provinces"] }"#)?;
+
+    Command::cargo_bin("validate")?.arg(root).assert().success();
+    Command::cargo_bin("validate")?
+        .arg(root)
+        .arg("--require-schemas")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("error[PAN005]: No schema in").and(predicate::str::contains("covers game/map/adjacency.yaml")));
+    Ok(())
+}
+
+// Integration test: --strict reports misspelled keys unless validate.yaml allows them
+#[test]
+fn validate_strict_reports_unknown_fields() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("game/units"))?;
+    std::fs::write(root.join("game/units/battalions.yaml"), "battalions:\n  - { id: inf, name: Infantry, nmae: typo, notes: hi }\n")?;
+    std::fs::write(root.join("validate.yaml"), "strict:\n  allow:\n    battalions: [notes]\n")?;
+
+    Command::cargo_bin("validate")?.arg(root).assert().success();
+    Command::cargo_bin("validate")?
+        .arg(root)
+        .arg("--strict")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown field `nmae` at battalions.0.nmae").and(predicate::str::contains("Unknown field `notes`").not()));
+    Ok(())
+}
+
+// Integration test: a mistyped flag is a usage error, not a skipped check
+#[test]
+fn validate_rejects_unknown_flags() -> Result<(), Box<dyn std::error::Error>> {
+    for flag in ["--deny-warning", "--require-schema"] {
+        Command::cargo_bin("validate")?
+            .arg(flag)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(format!("unknown option {}", flag)).and(predicate::str::contains("usage: validate")));
+    }
+    Ok(())
+}
+
+// Integration test: warnings only fail with --deny-warnings, and can be suppressed inline, errors cannot
+#[test]
+fn validate_deny_warnings_and_suppressions() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("game/map"))?;
+    let provinces = "provinces:\n  - { id: 0, name: A, pos: { x: 0, y: 0 }, unowned: true }\n";
+    std::fs::write(root.join("game/map/provinces.yaml"), provinces)?;
+
+    Command::cargo_bin("validate")?
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Validation complete: 0 error(s), 1 warning(s), 0 info."))
+        .stderr(predicate::str::contains("warning[PAN024]"));
+    Command::cargo_bin("validate")?.arg(root).arg("--deny-warnings").assert().failure();
+
+    // Raised to an error in validate.yaml
+    std::fs::write(root.join("validate.yaml"), "rules:\n  PAN024: error\n")?;
+    Command::cargo_bin("validate")?.arg(root).assert().failure().stderr(predicate::str::contains("error[PAN024]"));
+
+    let suppressed = provinces.replace("unowned: true }", "unowned: true }  # validate: allow PAN024");
+    std::fs::write(root.join("game/map/provinces.yaml"), suppressed)?;
+    Command::cargo_bin("validate")?.arg(root).assert().failure().stderr(predicate::str::contains("error[PAN024]"));
+
+    std::fs::remove_file(root.join("validate.yaml"))?;
+    Command::cargo_bin("validate")?
+        .arg(root)
+        .arg("--deny-warnings")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("0 warning(s), 0 info, 1 suppressed"));
+    Ok(())
+}
+
+// Integration test: --focus-report prints minimum days and dead focuses for one tag
+#[test]
+fn validate_focus_report_lists_days_and_unreachable_focuses() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("game/countries/GER"))?;
+    std::fs::write(
+        root.join("game/countries/GER/focus_tree.yaml"),
+        "focuses:
+  - { id: army, name: A, days: 30, mutually_exclusive: [navy] }
+  - { id: navy, name: N, days: 40, mutually_exclusive: [army] }
+  - { id: doctrine, name: D, days: 10, prerequisites: [army, navy] }
+  - { id: drill, name: X, days: 5, prerequisites: [army] }
+",
+    )?;
+
+    Command::cargo_bin("validate")?
+        .arg(root)
+        .args(["--focus-report", "GER"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("drill        35 days  via army -> drill"))
+        .stdout(predicate::str::contains("doctrine  unreachable: requires both army and navy"));
+    Ok(())
+}